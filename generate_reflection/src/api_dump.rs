@@ -9,8 +9,8 @@ use std::process::Command;
 use anyhow::Context;
 use rbx_dom_weak::types::VariantType;
 use rbx_reflection::{
-    ClassDescriptor, DataType, EnumDescriptor, PropertyDescriptor, PropertyKind,
-    PropertySerialization, PropertyTag, ReflectionDatabase, Scriptability,
+    ClassDescriptor, DataType, EnumDescriptor, PropertyDescriptor, PropertyKind, PropertySecurity,
+    PropertySerialization, PropertyTag, ReflectionDatabase, Scriptability, Security,
 };
 use roblox_install::RobloxStudio;
 use serde::Deserialize;
@@ -60,6 +60,7 @@ pub struct DumpClassProperty {
     pub value_type: ValueType,
     pub serialization: Serialization,
     pub security: PropertySecurity,
+    pub category: Option<String>,
 
     #[serde(default)]
     pub tags: Vec<String>,
@@ -87,23 +88,6 @@ pub enum ValueCategory {
     Class,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
-pub enum Security {
-    None,
-    LocalUserSecurity,
-    PluginSecurity,
-    RobloxScriptSecurity,
-    NotAccessibleSecurity,
-    RobloxSecurity,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct PropertySecurity {
-    read: Security,
-    write: Security,
-}
-
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct Serialization {
@@ -225,6 +209,8 @@ impl Dump {
                     property.scriptability = scriptability;
                     property.tags = tags;
                     property.kind = kind;
+                    property.security = Some(dump_property.security);
+                    property.category = dump_property.category.clone().map(Cow::Owned);
 
                     properties.insert(Cow::Owned(dump_property.name.clone()), property);
                 }
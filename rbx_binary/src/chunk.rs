@@ -4,7 +4,10 @@ use std::{
     str,
 };
 
-use crate::core::{RbxReadExt, RbxWriteExt};
+use crate::{
+    core::{RbxReadExt, RbxWriteExt},
+    deserializer::InnerError,
+};
 
 /// Represents one chunk from a binary model file.
 #[derive(Debug)]
@@ -15,25 +18,29 @@ pub struct Chunk {
 
 impl Chunk {
     /// Reads and decodes a `Chunk` from the given reader.
-    pub fn decode<R: Read>(mut reader: R) -> io::Result<Chunk> {
+    ///
+    /// Chunks that are cut off before the end of their data or that decode to
+    /// the wrong length return an error instead of a partial chunk.
+    pub fn decode<R: Read>(mut reader: R) -> Result<Chunk, InnerError> {
         let header = decode_chunk_header(&mut reader)?;
 
         log::trace!("{}", header);
 
         let data = if header.compressed_len == 0 {
-            let mut data = Vec::with_capacity(header.len as usize);
-            reader.take(header.len as u64).read_to_end(&mut data)?;
-            data
+            read_chunk_data(&mut reader, &header, header.len)?
         } else {
-            let mut compressed_data = Vec::with_capacity(header.compressed_len as usize);
-            reader
-                .take(header.compressed_len as u64)
-                .read_to_end(&mut compressed_data)?;
+            let compressed_data = read_chunk_data(&mut reader, &header, header.compressed_len)?;
 
             lz4::block::decompress(&compressed_data, Some(header.len as i32))?
         };
 
-        assert_eq!(data.len(), header.len as usize);
+        if data.len() != header.len as usize {
+            return Err(InnerError::BadChunkLength {
+                chunk_name: header.name_lossy(),
+                expected_len: header.len,
+                actual_len: data.len(),
+            });
+        }
 
         Ok(Chunk {
             name: header.name,
@@ -129,6 +136,16 @@ struct ChunkHeader {
     reserved: u32,
 }
 
+impl ChunkHeader {
+    /// Returns the chunk's name for use in error messages. Names shorter than
+    /// four bytes, like `END`, are padded with zeroes that are trimmed here.
+    fn name_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.name)
+            .trim_end_matches('\0')
+            .to_owned()
+    }
+}
+
 impl fmt::Display for ChunkHeader {
     fn fmt(&self, output: &mut fmt::Formatter) -> fmt::Result {
         let name = if let Ok(name) = str::from_utf8(&self.name) {
@@ -145,7 +162,20 @@ impl fmt::Display for ChunkHeader {
     }
 }
 
-fn decode_chunk_header<R: Read>(source: &mut R) -> io::Result<ChunkHeader> {
+fn decode_chunk_header<R: Read>(source: &mut R) -> Result<ChunkHeader, InnerError> {
+    let mut header = [0; 16];
+    source.read_exact(&mut header).map_err(|err| {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            InnerError::UnexpectedEof {
+                section: "a chunk header",
+            }
+        } else {
+            InnerError::from(err)
+        }
+    })?;
+
+    let mut source = &header[..];
+
     let mut name = [0; 4];
     source.read_exact(&mut name)?;
 
@@ -153,17 +183,40 @@ fn decode_chunk_header<R: Read>(source: &mut R) -> io::Result<ChunkHeader> {
     let len = source.read_le_u32()?;
     let reserved = source.read_le_u32()?;
 
-    if reserved != 0 {
-        panic!(
-            "Chunk reserved space was not zero, it was {}. This chunk may be malformed.",
-            reserved
-        );
-    }
-
-    Ok(ChunkHeader {
+    let header = ChunkHeader {
         name,
         compressed_len,
         len,
         reserved,
-    })
+    };
+
+    if reserved != 0 {
+        return Err(InnerError::BadChunkReserved {
+            chunk_name: header.name_lossy(),
+            reserved,
+        });
+    }
+
+    Ok(header)
+}
+
+/// Reads exactly `len` bytes of chunk data, without trusting `len` enough to
+/// allocate it all up front.
+fn read_chunk_data<R: Read>(
+    reader: &mut R,
+    header: &ChunkHeader,
+    len: u32,
+) -> Result<Vec<u8>, InnerError> {
+    let mut data = Vec::new();
+    reader.take(len as u64).read_to_end(&mut data)?;
+
+    if data.len() != len as usize {
+        return Err(InnerError::TruncatedChunk {
+            chunk_name: header.name_lossy(),
+            expected_len: len,
+            actual_len: data.len(),
+        });
+    }
+
+    Ok(data)
 }
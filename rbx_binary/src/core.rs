@@ -63,9 +63,22 @@ pub trait RbxReadExt: Read {
     fn read_binary_string(&mut self) -> io::Result<Vec<u8>> {
         let length = self.read_le_u32()?;
 
-        let mut value = Vec::with_capacity(length as usize);
+        // The length comes from the file, so we don't trust it enough to
+        // allocate it all up front.
+        let mut value = Vec::new();
         self.take(length as u64).read_to_end(&mut value)?;
 
+        if value.len() != length as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "string should be {} bytes long, but only {} bytes were left",
+                    length,
+                    value.len()
+                ),
+            ));
+        }
+
         Ok(value)
     }
 
@@ -73,11 +86,9 @@ pub trait RbxReadExt: Read {
     /// strings. This function isn't always appropriate because Roblox's formats
     /// generally aren't dilligent about data being valid Unicode.
     fn read_string(&mut self) -> io::Result<String> {
-        let length = self.read_le_u32()?;
-        let mut value = String::with_capacity(length as usize);
-        self.take(length as u64).read_to_string(&mut value)?;
+        let value = self.read_binary_string()?;
 
-        Ok(value)
+        String::from_utf8(value).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
     }
 
     fn read_bool(&mut self) -> io::Result<bool> {
@@ -11,6 +11,12 @@ pub struct Error {
     source: Box<InnerError>,
 }
 
+impl Error {
+    pub(crate) fn from_io(source: io::Error) -> Self {
+        InnerError::Io { source }.into()
+    }
+}
+
 impl From<InnerError> for Error {
     fn from(inner: InnerError) -> Self {
         Self {
@@ -30,6 +36,26 @@ pub(crate) enum InnerError {
     #[error("Invalid file header")]
     BadHeader,
 
+    #[error("File ended unexpectedly while reading {section}")]
+    UnexpectedEof { section: &'static str },
+
+    #[error("Chunk {chunk_name} should have {expected_len} bytes of data, but the file ended after {actual_len} bytes")]
+    TruncatedChunk {
+        chunk_name: String,
+        expected_len: u32,
+        actual_len: usize,
+    },
+
+    #[error("Chunk {chunk_name} should decompress to {expected_len} bytes, but it decompressed to {actual_len} bytes")]
+    BadChunkLength {
+        chunk_name: String,
+        expected_len: u32,
+        actual_len: usize,
+    },
+
+    #[error("Chunk {chunk_name} has reserved space that is not zero ({reserved}), so it may be malformed")]
+    BadChunkReserved { chunk_name: String, reserved: u32 },
+
     #[error("Unknown file version {version}. Known versions are: 0")]
     UnknownFileVersion { version: u16 },
 
@@ -1,4 +1,4 @@
-use std::io::Read;
+use std::io::{self, Read};
 
 use crate::core::{RbxReadExt, FILE_MAGIC_HEADER, FILE_SIGNATURE, FILE_VERSION};
 
@@ -18,6 +18,21 @@ pub(crate) struct FileHeader {
 
 impl FileHeader {
     pub(crate) fn decode<R: Read>(mut source: R) -> Result<Self, InnerError> {
+        // The header is a fixed size, so reading it all at once lets us report
+        // files that are too short to be models instead of a generic IO error.
+        let mut header = [0; 32];
+        source.read_exact(&mut header).map_err(|err| {
+            if err.kind() == io::ErrorKind::UnexpectedEof {
+                InnerError::UnexpectedEof {
+                    section: "the file header",
+                }
+            } else {
+                InnerError::from(err)
+            }
+        })?;
+
+        let mut source = &header[..];
+
         let mut magic_header = [0; 8];
         source.read_exact(&mut magic_header)?;
 
@@ -9,7 +9,7 @@ use rbx_reflection::ReflectionDatabase;
 
 use self::state::DeserializerState;
 
-pub(crate) use self::header::FileHeader;
+pub(crate) use self::{error::InnerError, header::FileHeader};
 
 pub use self::error::Error;
 
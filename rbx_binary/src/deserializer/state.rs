@@ -89,10 +89,13 @@ impl<'a, R: Read> DeserializerState<'a, R> {
         deserializer: &'a Deserializer<'a>,
         mut input: R,
     ) -> Result<Self, InnerError> {
-        let tree = WeakDom::new(InstanceBuilder::new("DataModel"));
-
         let header = FileHeader::decode(&mut input)?;
 
+        let tree = WeakDom::with_capacity(
+            InstanceBuilder::new("DataModel"),
+            1 + header.num_instances as usize,
+        );
+
         let type_infos = HashMap::with_capacity(header.num_types as usize);
         let instances_by_ref = HashMap::with_capacity(1 + header.num_instances as usize);
 
@@ -110,7 +113,7 @@ impl<'a, R: Read> DeserializerState<'a, R> {
     }
 
     pub(super) fn next_chunk(&mut self) -> Result<Chunk, InnerError> {
-        Ok(Chunk::decode(&mut self.input)?)
+        Chunk::decode(&mut self.input)
     }
 
     pub(super) fn decode_meta_chunk(&mut self, mut chunk: &[u8]) -> Result<(), InnerError> {
@@ -297,8 +300,18 @@ impl<'a, R: Read> DeserializerState<'a, R> {
                 VariantType::String => {
                     for referent in &type_info.referents {
                         let instance = self.instances_by_ref.get_mut(referent).unwrap();
-                        let value = chunk.read_string()?;
-                        instance.builder.add_property(&canonical_name, value);
+                        let value = chunk.read_binary_string()?;
+
+                        // Roblox doesn't check that strings are valid UTF-8,
+                        // so some String properties hold arbitrary bytes.
+                        // Those are kept as BinaryStrings instead of failing.
+                        match String::from_utf8(value) {
+                            Ok(value) => instance.builder.add_property(&canonical_name, value),
+                            Err(err) => instance.builder.add_property(
+                                &canonical_name,
+                                BinaryString::from(err.into_bytes()),
+                            ),
+                        }
                     }
                 }
                 VariantType::Content => {
@@ -66,7 +66,11 @@ mod text_deserializer;
 #[cfg(test)]
 mod tests;
 
-use std::io::{Read, Write};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+    path::Path,
+};
 
 use rbx_dom_weak::{types::Ref, WeakDom};
 
@@ -78,7 +82,7 @@ pub mod text_format {
 
 pub use crate::{
     deserializer::{Deserializer, Error as DecodeError},
-    serializer::{Error as EncodeError, Serializer},
+    serializer::{EmptyNameBehavior, Error as EncodeError, Serializer},
 };
 
 /// Deserialize a Roblox binary model or place from a stream.
@@ -86,6 +90,15 @@ pub fn from_reader<R: Read>(reader: R) -> Result<WeakDom, DecodeError> {
     Deserializer::new().deserialize(reader)
 }
 
+/// Deserialize a Roblox binary model or place from the file at the given path,
+/// reading it through a buffer.
+///
+/// Failing to open the file is reported as a `DecodeError`.
+pub fn from_path<P: AsRef<Path>>(path: P) -> Result<WeakDom, DecodeError> {
+    let file = File::open(path).map_err(DecodeError::from_io)?;
+    from_reader(BufReader::new(file))
+}
+
 /// Serializes a subset of the given DOM to a binary format model or place,
 /// writing to something that implements the `std::io::Write` trait.
 pub fn to_writer<W: Write>(writer: W, dom: &WeakDom, refs: &[Ref]) -> Result<(), EncodeError> {
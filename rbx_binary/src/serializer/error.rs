@@ -47,4 +47,17 @@ pub(super) enum InnerError {
 
     #[error("The instance with referent {referent:?} was not present in the dom.")]
     InvalidInstanceId { referent: Ref },
+
+    #[error("An instance of class {class_name} has an empty name")]
+    EmptyName { class_name: String },
+
+    #[error(
+        "Property {type_name}.{prop_name} on instance {instance_full_name} is a Content that \
+        points to an object, which can't be written to a binary file"
+    )]
+    ContentObject {
+        type_name: String,
+        prop_name: String,
+        instance_full_name: String,
+    },
 }
@@ -11,6 +11,12 @@ pub use self::error::Error;
 
 /// A configurable serializer for Roblox binary models and places.
 ///
+/// Properties in an instance's `unknown_properties` are not written, since
+/// they are stored in another format's serialized form that can't be converted
+/// without knowing their type. This includes properties that rbx_xml left
+/// undecoded because of `DecodeOptions::lazy_properties`, which need to be
+/// decoded with `rbx_xml::get_property` first.
+///
 /// ## Example
 /// ```no_run
 /// use std::fs::File;
@@ -31,18 +37,43 @@ pub use self::error::Error;
 // * reflection_database: Option<ReflectionDatabase> = default
 // * recursive: bool = true
 #[non_exhaustive]
-pub struct Serializer {}
+pub struct Serializer {
+    respect_archivable: bool,
+    empty_name_behavior: EmptyNameBehavior,
+}
 
 impl Serializer {
     /// Create a new `Serializer` with the default settings.
     pub fn new() -> Self {
-        Serializer {}
+        Serializer {
+            respect_archivable: false,
+            empty_name_behavior: EmptyNameBehavior::Keep,
+        }
+    }
+
+    /// Sets whether instances with their `Archivable` property set to `false`
+    /// should be left out, along with all of their descendants. This matches
+    /// how Roblox Studio saves files. It's off by default.
+    pub fn respect_archivable(self, respect_archivable: bool) -> Self {
+        Self {
+            respect_archivable,
+            ..self
+        }
+    }
+
+    /// Sets what the serializer should do when it writes an instance with an
+    /// empty name. By default, the empty name is written as-is.
+    pub fn empty_name_behavior(self, empty_name_behavior: EmptyNameBehavior) -> Self {
+        Self {
+            empty_name_behavior,
+            ..self
+        }
     }
 
     /// Serialize a Roblox binary model or place into the given stream using
     /// this serializer.
     pub fn serialize<W: Write>(&self, writer: W, dom: &WeakDom, refs: &[Ref]) -> Result<(), Error> {
-        let mut serializer = SerializerState::new(dom, writer);
+        let mut serializer = SerializerState::new(self, dom, writer);
 
         serializer.add_instances(refs)?;
         serializer.generate_referents();
@@ -63,3 +94,21 @@ impl Default for Serializer {
         Self::new()
     }
 }
+
+/// Describes what rbx_binary should do when it writes an instance with an
+/// empty name. Roblox Studio behaves confusingly with instances like these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EmptyNameBehavior {
+    /// Writes the empty name as-is.
+    ///
+    /// This is the default.
+    Keep,
+
+    /// Writes the instance's class name as its name instead, like Roblox
+    /// does for new instances.
+    UseClassName,
+
+    /// Returns an error.
+    Error,
+}
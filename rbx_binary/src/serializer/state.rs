@@ -26,7 +26,7 @@ use crate::{
     types::Type,
 };
 
-use super::error::InnerError;
+use super::{error::InnerError, EmptyNameBehavior, Serializer};
 
 static FILE_FOOTER: &[u8] = b"</roblox>";
 
@@ -34,6 +34,8 @@ static FILE_FOOTER: &[u8] = b"</roblox>";
 /// `BinarySerializer` object should be created every time we want to serialize
 /// a binary model file.
 pub(super) struct SerializerState<'a, W> {
+    serializer: &'a Serializer,
+
     /// The dom containing all of the instances that we're serializing.
     dom: &'a WeakDom,
 
@@ -211,8 +213,9 @@ impl TypeInfos {
 }
 
 impl<'a, W: Write> SerializerState<'a, W> {
-    pub fn new(dom: &'a WeakDom, output: W) -> Self {
+    pub fn new(serializer: &'a Serializer, dom: &'a WeakDom, output: W) -> Self {
         SerializerState {
+            serializer,
             dom,
             output,
             relevant_instances: Vec::new(),
@@ -223,6 +226,15 @@ impl<'a, W: Write> SerializerState<'a, W> {
         }
     }
 
+    /// Tells whether the instance with the given referent should be saved,
+    /// which is only false if its `Archivable` property is explicitly false.
+    fn is_archivable(&self, referent: Ref) -> bool {
+        self.dom
+            .get_by_ref(referent)
+            .and_then(|instance| instance.properties.get("Archivable"))
+            != Some(&Variant::Bool(false))
+    }
+
     /// Mark the given instance IDs and all of their descendants as intended for
     /// serialization with this serializer.
     pub fn add_instances(&mut self, referents: &[Ref]) -> Result<(), InnerError> {
@@ -230,11 +242,24 @@ impl<'a, W: Write> SerializerState<'a, W> {
         to_visit.extend(referents);
 
         while let Some(referent) = to_visit.pop_front() {
+            if self.serializer.respect_archivable && !self.is_archivable(referent) {
+                continue;
+            }
+
             self.relevant_instances.push(referent);
             self.collect_type_info(referent)?;
 
             // TODO: Turn into error
             let instance = self.dom.get_by_ref(referent).unwrap();
+
+            if instance.name.is_empty()
+                && self.serializer.empty_name_behavior == EmptyNameBehavior::Error
+            {
+                return Err(InnerError::EmptyName {
+                    class_name: instance.class.clone(),
+                });
+            }
+
             to_visit.extend(instance.children());
         }
 
@@ -508,6 +533,7 @@ impl<'a, W: Write> SerializerState<'a, W> {
                 chunk.write_u8(prop_info.prop_type as u8)?;
 
                 let dom = &self.dom;
+                let empty_name_behavior = self.serializer.empty_name_behavior;
                 let values = type_info
                     .object_refs
                     .iter()
@@ -521,7 +547,15 @@ impl<'a, W: Write> SerializerState<'a, W> {
                         // convenience, but when serializing to the binary model
                         // format we need to handle it just like other properties.
                         if prop_name == "Name" {
-                            return Cow::Owned(Variant::String(instance.name.clone()));
+                            let name = if instance.name.is_empty()
+                                && empty_name_behavior == EmptyNameBehavior::UseClassName
+                            {
+                                &instance.class
+                            } else {
+                                &instance.name
+                            };
+
+                            return Cow::Owned(Variant::String(name.clone()));
                         }
 
                         // Most properties will be stored on instances using the
@@ -567,6 +601,18 @@ impl<'a, W: Write> SerializerState<'a, W> {
                                     chunk.write_string(&value)?;
                                 }
                                 Variant::Content(value) => {
+                                    // Binary files can only hold the URL of a
+                                    // Content, so writing one that points to an
+                                    // object would lose it.
+                                    if value.as_object().is_some() {
+                                        return Err(InnerError::ContentObject {
+                                            type_name: type_name.clone(),
+                                            prop_name: prop_name.to_string(),
+                                            instance_full_name: self
+                                                .full_name_for(type_info.object_refs[i]),
+                                        });
+                                    }
+
                                     chunk.write_string(value.as_ref())?;
                                 }
                                 Variant::BinaryString(value) => {
@@ -0,0 +1,137 @@
+use rbx_dom_weak::{
+    types::{BinaryString, Variant},
+    InstanceBuilder, WeakDom,
+};
+
+use crate::{from_reader, to_writer};
+
+fn encode_folder() -> Vec<u8> {
+    let tree = WeakDom::new(InstanceBuilder::new("Folder"));
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, &[tree.root_ref()]).expect("failed to encode model");
+
+    buffer
+}
+
+/// A file that ends partway through its header should return an error instead
+/// of panicking.
+#[test]
+fn truncated_header() {
+    let buffer = encode_folder();
+
+    let err = from_reader(&buffer[..10]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "File ended unexpectedly while reading the file header"
+    );
+}
+
+/// Files that don't start with the binary model magic bytes, like XML models,
+/// should be rejected.
+#[test]
+fn wrong_magic() {
+    let mut buffer = encode_folder();
+    buffer[..8].copy_from_slice(b"<roblox ");
+
+    let err = from_reader(buffer.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid file header");
+}
+
+/// A chunk whose data is cut off should report how much data was missing.
+#[test]
+fn truncated_chunk() {
+    let buffer = encode_folder();
+
+    // The file ends with an uncompressed END chunk containing "</roblox>".
+    let err = from_reader(&buffer[..buffer.len() - 3]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Chunk END should have 9 bytes of data, but the file ended after 6 bytes"
+    );
+
+    let err = from_reader(&buffer[..buffer.len() - 20]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "File ended unexpectedly while reading a chunk header"
+    );
+}
+
+/// Every value in a PROP chunk for strings is stored with its own length, so
+/// values containing null bytes shouldn't bleed into each other.
+#[test]
+fn binary_strings_with_nulls() {
+    let values: &[&[u8]] = &[b"Tag1\0Tag2", b"", b"\0\0Last\0"];
+
+    let tree = WeakDom::new(
+        InstanceBuilder::new("Folder").with_children(values.iter().map(|&value| {
+            InstanceBuilder::new("Folder").with_property("Tags", BinaryString::from(value))
+        })),
+    );
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, &[tree.root_ref()]).unwrap();
+
+    let decoded = from_reader(buffer.as_slice()).unwrap();
+    let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+
+    let decoded_values: Vec<_> = folder
+        .children()
+        .iter()
+        .map(|&referent| {
+            decoded
+                .get_by_ref(referent)
+                .unwrap()
+                .properties
+                .get("Tags")
+                .cloned()
+        })
+        .collect();
+
+    let expected: Vec<_> = values
+        .iter()
+        .map(|&value| Some(Variant::BinaryString(BinaryString::from(value))))
+        .collect();
+
+    assert_eq!(decoded_values, expected);
+}
+
+/// String properties that aren't valid UTF-8 are read as BinaryStrings instead
+/// of failing to decode the whole file.
+#[test]
+fn invalid_utf8_string() {
+    let tree = WeakDom::new(
+        InstanceBuilder::new("Folder")
+            .with_child(InstanceBuilder::value_instance(
+                "StringValue",
+                BinaryString::from(&[0xff, 0x00, 0xfe][..]),
+            ))
+            .with_child(InstanceBuilder::string_value("Hello\0world")),
+    );
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, &[tree.root_ref()]).unwrap();
+
+    let decoded = from_reader(buffer.as_slice()).unwrap();
+    let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+    let values: Vec<_> = folder
+        .children()
+        .iter()
+        .map(|&referent| {
+            decoded
+                .get_by_ref(referent)
+                .unwrap()
+                .properties
+                .get("Value")
+        })
+        .collect();
+
+    assert_eq!(
+        values,
+        vec![
+            Some(&Variant::BinaryString(BinaryString::from(
+                &[0xff, 0x00, 0xfe][..]
+            ))),
+            Some(&Variant::String("Hello\0world".to_owned())),
+        ]
+    );
+}
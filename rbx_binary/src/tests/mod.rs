@@ -1,3 +1,4 @@
+mod deserializer;
 mod models;
 mod serializer;
 mod util;
@@ -1,9 +1,15 @@
 use rbx_dom_weak::{
-    types::{Color3, Color3uint8, Ref, Region3, Vector3},
+    types::{
+        CFrame, Color3, Color3uint8, Content, Enum, Matrix3, Ref, Region3, UDim, UDim2, Variant,
+        Vector3,
+    },
     InstanceBuilder, WeakDom,
 };
 
-use crate::{text_deserializer::DecodedModel, to_writer};
+use crate::{
+    core::transform_i32, from_path, from_reader, text_deserializer::DecodedModel, to_writer,
+    EmptyNameBehavior, Serializer,
+};
 
 /// A basic test to make sure we can serialize the simplest instance: a Folder.
 #[test]
@@ -153,3 +159,347 @@ fn part_color() {
     let decoded = DecodedModel::from_reader(buf.as_slice());
     insta::assert_yaml_snapshot!(decoded);
 }
+
+/// Ensures that referents, which are stored delta-encoded in the INST and PRNT
+/// chunks, resolve to the correct parents when read back.
+#[test]
+fn nested_folders_round_trip() {
+    const DEPTH: usize = 10;
+
+    let mut builder = InstanceBuilder::new("Folder").with_name("0");
+    for i in 1..DEPTH {
+        builder = InstanceBuilder::new("Folder")
+            .with_name(i.to_string())
+            .with_child(builder);
+    }
+
+    let tree = WeakDom::new(builder);
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, &[tree.root_ref()]).expect("failed to encode model");
+
+    let decoded = from_reader(buffer.as_slice()).expect("failed to decode model");
+
+    let mut depth = 0;
+    let mut current = decoded.root();
+    while let [child_ref] = current.children() {
+        let child = decoded.get_by_ref(*child_ref).unwrap();
+        assert_eq!(child.name, (DEPTH - depth - 1).to_string());
+        assert_eq!(child.parent(), current.referent());
+
+        current = child;
+        depth += 1;
+    }
+
+    assert!(current.children().is_empty());
+    assert_eq!(depth, DEPTH);
+}
+
+/// Ensures that Float64 values with large magnitudes survive a round trip.
+#[test]
+fn float64_round_trip() {
+    let values = [1.7976931348623157e308, -1.0e300, 5.0e-324];
+
+    let tree = WeakDom::new(
+        InstanceBuilder::new("Folder").with_children(
+            values
+                .iter()
+                .map(|&value| InstanceBuilder::new("NumberValue").with_property("Value", value)),
+        ),
+    );
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, tree.root().children()).expect("failed to encode model");
+
+    let decoded = from_reader(buffer.as_slice()).expect("failed to decode model");
+    assert_eq!(decoded.root().children().len(), values.len());
+
+    for (&child_ref, &expected) in decoded.root().children().iter().zip(values.iter()) {
+        let child = decoded.get_by_ref(child_ref).unwrap();
+        assert_eq!(
+            child.properties.get("Value"),
+            Some(&Variant::Float64(expected))
+        );
+    }
+}
+
+/// Ensures that Int32 values, which are zigzag encoded and interleaved in the
+/// binary format, come back exactly, including negative values.
+#[test]
+fn int32_round_trip() {
+    let values = [-5, 0, std::i32::MAX];
+
+    assert_eq!(transform_i32(-5), 9);
+    assert_eq!(transform_i32(0), 0);
+    assert_eq!(transform_i32(std::i32::MAX), -2);
+
+    let tree = WeakDom::new(
+        InstanceBuilder::new("Folder").with_children(
+            values
+                .iter()
+                .map(|&value| InstanceBuilder::new("Frame").with_property("ZIndex", value)),
+        ),
+    );
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, tree.root().children()).expect("failed to encode model");
+
+    let decoded = from_reader(buffer.as_slice()).expect("failed to decode model");
+    assert_eq!(decoded.root().children().len(), values.len());
+
+    for (&child_ref, &expected) in decoded.root().children().iter().zip(values.iter()) {
+        let child = decoded.get_by_ref(child_ref).unwrap();
+        assert_eq!(
+            child.properties.get("ZIndex"),
+            Some(&Variant::Int32(expected))
+        );
+    }
+}
+
+/// Ensures that Int64 values near the edges of their range survive a round
+/// trip through the zigzag and interleaving transforms.
+#[test]
+fn int64_round_trip() {
+    let values = [std::i64::MAX, std::i64::MAX - 1, std::i64::MIN, -1];
+
+    let tree = WeakDom::new(
+        InstanceBuilder::new("Folder").with_children(
+            values
+                .iter()
+                .map(|&value| InstanceBuilder::new("IntValue").with_property("Value", value)),
+        ),
+    );
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, tree.root().children()).expect("failed to encode model");
+
+    let decoded = from_reader(buffer.as_slice()).expect("failed to decode model");
+    assert_eq!(decoded.root().children().len(), values.len());
+
+    for (&child_ref, &expected) in decoded.root().children().iter().zip(values.iter()) {
+        let child = decoded.get_by_ref(child_ref).unwrap();
+        assert_eq!(
+            child.properties.get("Value"),
+            Some(&Variant::Int64(expected))
+        );
+    }
+}
+
+/// Ensures that multi-component values, which are stored one component at a
+/// time across every instance, are reassembled into the right instances.
+#[test]
+fn interleaved_components_round_trip() {
+    let expected = [
+        (
+            Vector3::new(1.0, 2.0, 3.0),
+            CFrame::new(Vector3::new(4.0, 5.0, 6.0), Matrix3::identity()),
+            Color3::new(0.1, 0.2, 0.3),
+            UDim2::new(UDim::new(0.25, 10), UDim::new(0.5, 20)),
+        ),
+        (
+            Vector3::new(-7.0, -8.0, -9.0),
+            CFrame::new(
+                Vector3::new(-10.0, 11.0, -12.0),
+                Matrix3::new(
+                    Vector3::new(0.0, 1.0, 0.0),
+                    Vector3::new(-1.0, 0.0, 0.0),
+                    Vector3::new(0.0, 0.0, 1.0),
+                ),
+            ),
+            Color3::new(0.4, 0.5, 0.6),
+            UDim2::new(UDim::new(0.75, -30), UDim::new(1.0, -40)),
+        ),
+    ];
+
+    let tree = WeakDom::new(
+        InstanceBuilder::new("Folder").with_children(expected.iter().map(
+            |(position, cframe, color, size)| {
+                InstanceBuilder::new("Frame")
+                    .with_property("Size", *size)
+                    .with_property("BackgroundColor3", *color)
+                    .with_child(
+                        InstanceBuilder::new("Vector3Value").with_property("Value", *position),
+                    )
+                    .with_child(InstanceBuilder::new("CFrameValue").with_property("Value", *cframe))
+            },
+        )),
+    );
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, tree.root().children()).expect("failed to encode model");
+
+    let decoded = from_reader(buffer.as_slice()).expect("failed to decode model");
+    let frames = decoded.root().children();
+    assert_eq!(frames.len(), expected.len());
+
+    for (&frame_ref, (position, cframe, color, size)) in frames.iter().zip(expected.iter()) {
+        let frame = decoded.get_by_ref(frame_ref).unwrap();
+        assert_eq!(frame.properties.get("Size"), Some(&Variant::UDim2(*size)));
+        assert_eq!(
+            frame.properties.get("BackgroundColor3"),
+            Some(&Variant::Color3(*color))
+        );
+
+        let vector3_value = decoded.get_by_ref(frame.children()[0]).unwrap();
+        assert_eq!(
+            vector3_value.properties.get("Value"),
+            Some(&Variant::Vector3(*position))
+        );
+
+        let cframe_value = decoded.get_by_ref(frame.children()[1]).unwrap();
+        assert_eq!(
+            cframe_value.properties.get("Value"),
+            Some(&Variant::CFrame(*cframe))
+        );
+    }
+}
+
+/// Ensures that OptionalCFrame properties, which are written as a CFrame array
+/// followed by an array of presence flags, round trip whether or not they
+/// have a value.
+#[test]
+fn optional_cframe_round_trip() {
+    let present = CFrame::new(
+        Vector3::new(1.0, 2.0, 3.0),
+        Matrix3::new(
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        ),
+    );
+    let values = [None, Some(present), None];
+
+    let tree = WeakDom::new(
+        InstanceBuilder::new("Folder").with_children(values.iter().map(|&value| {
+            InstanceBuilder::new("Model")
+                .with_property("WorldPivotData", Variant::OptionalCFrame(value))
+                .with_property("LevelOfDetail", Enum::from_u32(1))
+        })),
+    );
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, tree.root().children()).expect("failed to encode model");
+
+    let decoded = from_reader(buffer.as_slice()).expect("failed to decode model");
+    assert_eq!(decoded.root().children().len(), values.len());
+
+    for (&child_ref, &expected) in decoded.root().children().iter().zip(values.iter()) {
+        let child = decoded.get_by_ref(child_ref).unwrap();
+        assert_eq!(
+            child.properties.get("WorldPivotData"),
+            Some(&Variant::OptionalCFrame(expected))
+        );
+        assert_eq!(
+            child.properties.get("LevelOfDetail"),
+            Some(&Variant::Enum(Enum::from_u32(1)))
+        );
+    }
+}
+
+/// Ensures that instances with Archivable set to false are left out along with
+/// their descendants when the serializer is told to respect it.
+#[test]
+fn respect_archivable() {
+    let tree = WeakDom::new(
+        InstanceBuilder::new("Folder")
+            .with_child(
+                InstanceBuilder::new("Folder")
+                    .with_name("Temporary")
+                    .with_property("Archivable", false)
+                    .with_child(InstanceBuilder::new("Part")),
+            )
+            .with_child(InstanceBuilder::new("Folder").with_name("Kept")),
+    );
+
+    let mut buffer = Vec::new();
+    Serializer::new()
+        .respect_archivable(true)
+        .serialize(&mut buffer, &tree, &[tree.root_ref()])
+        .expect("failed to encode model");
+
+    let decoded = from_reader(buffer.as_slice()).expect("failed to decode model");
+    let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+    assert_eq!(folder.children().len(), 1);
+
+    let kept = decoded.get_by_ref(folder.children()[0]).unwrap();
+    assert_eq!(kept.name, "Kept");
+    assert!(kept.children().is_empty());
+}
+
+/// Ensures that instances with empty names are written as-is, renamed to their
+/// class name, or rejected, depending on the serializer's settings.
+#[test]
+fn empty_name_behavior() {
+    let tree = WeakDom::new(InstanceBuilder::new("Folder").with_name(""));
+
+    let encode = |behavior| {
+        let mut buffer = Vec::new();
+        Serializer::new()
+            .empty_name_behavior(behavior)
+            .serialize(&mut buffer, &tree, &[tree.root_ref()])
+            .map(|_| buffer)
+    };
+
+    let decoded_name = |buffer: Vec<u8>| {
+        let decoded = from_reader(buffer.as_slice()).expect("failed to decode model");
+        let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+        folder.name.clone()
+    };
+
+    let kept = encode(EmptyNameBehavior::Keep).expect("failed to encode model");
+    assert_eq!(decoded_name(kept), "");
+
+    let renamed = encode(EmptyNameBehavior::UseClassName).expect("failed to encode model");
+    assert_eq!(decoded_name(renamed), "Folder");
+
+    let error = encode(EmptyNameBehavior::Error).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "An instance of class Folder has an empty name"
+    );
+}
+
+/// Ensures that Content properties pointing to an object are rejected instead
+/// of being written as an empty URL, since binary files can't hold objects.
+#[test]
+fn content_object() {
+    let mut tree = WeakDom::new(InstanceBuilder::new("ScreenGui"));
+    let root_ref = tree.root_ref();
+    let label_ref = tree.insert(
+        root_ref,
+        InstanceBuilder::new("ImageLabel").with_property("Image", Content::from_object(root_ref)),
+    );
+
+    let mut buffer = Vec::new();
+    let error = to_writer(&mut buffer, &tree, &[label_ref]).unwrap_err();
+    assert!(error.to_string().contains("points to an object"));
+
+    tree.get_by_ref_mut(label_ref)
+        .unwrap()
+        .set_property("Image", Content::from("rbxassetid://123"));
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, &[label_ref]).expect("failed to encode model");
+}
+
+/// Ensures that models can be loaded straight from a file, and that failing
+/// to open the file is reported as an error.
+#[test]
+fn load_from_path() {
+    let tree = WeakDom::new(InstanceBuilder::new("Folder").with_name("OnDisk"));
+    let path =
+        std::env::temp_dir().join(format!("rbx_binary-from-path-{}.rbxm", std::process::id()));
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &tree, &[tree.root_ref()]).expect("failed to encode model");
+    std::fs::write(&path, buffer).unwrap();
+
+    let decoded = from_path(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let decoded = decoded.expect("failed to decode model");
+    let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+    assert_eq!(folder.name, "OnDisk");
+
+    assert!(from_path(&path).is_err());
+}
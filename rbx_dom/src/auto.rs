@@ -0,0 +1,190 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use rbx_dom_weak::WeakDom;
+use thiserror::Error;
+
+static BINARY_MAGIC_HEADER: &[u8] = b"<roblox!";
+static XML_MAGIC_HEADER: &[u8] = b"<roblox";
+static UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// How many bytes from the start of a file are looked at to figure out its
+/// format. XML files can have a byte order mark and whitespace before the
+/// `<roblox>` tag, so this is more than just the length of the magic header.
+const SNIFF_LENGTH: u64 = 1024;
+
+/// Represents an error that occurred while decoding a file with
+/// [`decode_auto`].
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct DecodeError {
+    source: Box<InnerError>,
+}
+
+impl From<InnerError> for DecodeError {
+    fn from(inner: InnerError) -> Self {
+        Self {
+            source: Box::new(inner),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum InnerError {
+    #[error(transparent)]
+    Io {
+        #[from]
+        source: io::Error,
+    },
+
+    #[error(transparent)]
+    Xml {
+        #[from]
+        source: rbx_xml::DecodeError,
+    },
+
+    #[error(transparent)]
+    Binary {
+        #[from]
+        source: rbx_binary::DecodeError,
+    },
+
+    #[error("Unknown file format: expected an XML or binary model or place file")]
+    UnknownFormat,
+}
+
+/// Decodes a model or place file that can be in either Roblox's XML or binary
+/// format, detecting which one it is by looking at the start of the file.
+///
+/// The XML format is decoded using rbx_xml's default options.
+pub fn decode_auto<R: Read + Seek>(reader: R) -> Result<WeakDom, DecodeError> {
+    Ok(decode_auto_inner(reader)?)
+}
+
+/// Decodes the model or place file at the given path, which can be in either
+/// Roblox's XML or binary format, like [`decode_auto`].
+pub fn decode_auto_path<P: AsRef<Path>>(path: P) -> Result<WeakDom, DecodeError> {
+    let file = File::open(path).map_err(InnerError::from)?;
+    Ok(decode_auto_inner(BufReader::new(file))?)
+}
+
+fn decode_auto_inner<R: Read + Seek>(mut reader: R) -> Result<WeakDom, InnerError> {
+    let start = reader.seek(SeekFrom::Current(0))?;
+
+    let mut header = Vec::new();
+    (&mut reader).take(SNIFF_LENGTH).read_to_end(&mut header)?;
+
+    reader.seek(SeekFrom::Start(start))?;
+
+    if header.starts_with(BINARY_MAGIC_HEADER) {
+        Ok(rbx_binary::from_reader(reader)?)
+    } else if skip_xml_preamble(&header).starts_with(XML_MAGIC_HEADER) {
+        Ok(rbx_xml::from_reader_default(reader)?)
+    } else {
+        Err(InnerError::UnknownFormat)
+    }
+}
+
+/// Skips over a UTF-8 byte order mark and any whitespace at the start of an
+/// XML file.
+fn skip_xml_preamble(header: &[u8]) -> &[u8] {
+    let header = if header.starts_with(UTF8_BOM) {
+        &header[UTF8_BOM.len()..]
+    } else {
+        header
+    };
+
+    let start = header
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(header.len());
+
+    &header[start..]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use rbx_dom_weak::InstanceBuilder;
+
+    fn test_dom() -> WeakDom {
+        WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(InstanceBuilder::new("StringValue").with_name("Hello")),
+        )
+    }
+
+    fn assert_decoded(decoded: &WeakDom) {
+        let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+        assert_eq!(folder.class, "Folder");
+
+        let child = decoded.get_by_ref(folder.children()[0]).unwrap();
+        assert_eq!(child.class, "StringValue");
+        assert_eq!(child.name, "Hello");
+    }
+
+    #[test]
+    fn auto_binary() {
+        let dom = test_dom();
+
+        let mut buffer = Vec::new();
+        rbx_binary::to_writer(&mut buffer, &dom, &[dom.root_ref()]).unwrap();
+
+        let decoded = decode_auto(Cursor::new(buffer)).unwrap();
+        assert_decoded(&decoded);
+    }
+
+    #[test]
+    fn auto_xml() {
+        let dom = test_dom();
+
+        let mut buffer = Vec::new();
+        rbx_xml::to_writer_default(&mut buffer, &dom, &[dom.root_ref()]).unwrap();
+
+        let decoded = decode_auto(Cursor::new(buffer)).unwrap();
+        assert_decoded(&decoded);
+    }
+
+    #[test]
+    fn auto_xml_preamble() {
+        let dom = test_dom();
+
+        let mut buffer = UTF8_BOM.to_vec();
+        buffer.extend_from_slice(b"\r\n  \t");
+        rbx_xml::to_writer_default(&mut buffer, &dom, &[dom.root_ref()]).unwrap();
+
+        let decoded = decode_auto(Cursor::new(buffer)).unwrap();
+        assert_decoded(&decoded);
+    }
+
+    #[test]
+    fn auto_path() {
+        let dom = test_dom();
+        let path = std::env::temp_dir().join(format!("rbx_dom-auto-{}.rbxm", std::process::id()));
+
+        let mut buffer = Vec::new();
+        rbx_binary::to_writer(&mut buffer, &dom, &[dom.root_ref()]).unwrap();
+        std::fs::write(&path, buffer).unwrap();
+
+        let decoded = decode_auto_path(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_decoded(&decoded.unwrap());
+
+        assert!(decode_auto_path(&path).is_err());
+    }
+
+    #[test]
+    fn auto_unknown() {
+        let result = decode_auto(Cursor::new(b"{\"not\": \"a model\"}".to_vec()));
+        assert!(result.is_err());
+
+        let result = decode_auto(Cursor::new(Vec::new()));
+        assert!(result.is_err());
+    }
+}
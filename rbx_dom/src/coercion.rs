@@ -0,0 +1,153 @@
+use std::convert::TryFrom;
+
+use rbx_dom_weak::{
+    types::{CFrame, Variant, VariantType},
+    WeakDom,
+};
+use rbx_reflection::ReflectionDatabase;
+
+use crate::reflection::expected_type;
+
+/// Converts the properties of every instance in the given `WeakDom` to the
+/// types that the given reflection database expects, where that can be done
+/// without changing their meaning.
+///
+/// The following conversions are performed:
+/// - `Float32` and `Float64` to each other
+/// - `Int32` to `Int64`, and `Int64` to `Int32` when the value fits
+/// - `Color3` and `Color3uint8` to each other
+/// - `BrickColor` to `Color3` or `Color3uint8`
+/// - `Vector3` to a `CFrame` at that position with no rotation, for files
+///   that stored only a position in properties that are now `CFrame`s
+///
+/// Properties that aren't known by the reflection database are left alone, as
+/// are properties that can't be converted. The latter are logged as warnings.
+pub fn coerce_types(dom: &mut WeakDom, database: &ReflectionDatabase) {
+    let mut to_visit = vec![dom.root_ref()];
+
+    while let Some(referent) = to_visit.pop() {
+        let instance = dom.get_by_ref_mut(referent).unwrap();
+        to_visit.extend_from_slice(instance.children());
+
+        for (property_name, value) in &mut instance.properties {
+            let descriptor = match database.find_canonical_property(&instance.class, property_name)
+            {
+                Some(descriptor) => descriptor,
+                None => continue,
+            };
+
+            let expected_type = match expected_type(&descriptor.data_type) {
+                Some(expected_type) => expected_type,
+                None => continue,
+            };
+
+            if value.ty() == expected_type {
+                continue;
+            }
+
+            match coerce_value(value, expected_type) {
+                Some(coerced) => *value = coerced,
+                None => log::warn!(
+                    "Could not convert property {}.{} from {} to {}",
+                    instance.class,
+                    property_name,
+                    value.ty(),
+                    expected_type
+                ),
+            }
+        }
+    }
+}
+
+pub(crate) fn coerce_value(value: &Variant, expected_type: VariantType) -> Option<Variant> {
+    match (value, expected_type) {
+        (Variant::Float64(value), VariantType::Float32) => Some(Variant::Float32(*value as f32)),
+        (Variant::Float32(value), VariantType::Float64) => Some(Variant::Float64(*value as f64)),
+        (Variant::Int32(value), VariantType::Int64) => Some(Variant::Int64(*value as i64)),
+        (Variant::Int64(value), VariantType::Int32) => {
+            i32::try_from(*value).ok().map(Variant::Int32)
+        }
+        (Variant::BrickColor(value), VariantType::Color3) => {
+            Some(Variant::Color3(value.to_color3uint8().into()))
+        }
+        (Variant::BrickColor(value), VariantType::Color3uint8) => {
+            Some(Variant::Color3uint8(value.to_color3uint8()))
+        }
+        (Variant::Vector3(position), VariantType::CFrame) => {
+            Some(Variant::CFrame(CFrame::from_position(*position)))
+        }
+        (_, VariantType::Color3) => value.coerce_color3().map(Variant::Color3),
+        (_, VariantType::Color3uint8) => value.coerce_color3uint8().map(Variant::Color3uint8),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::{
+        types::{Color3, Color3uint8, Matrix3, Vector3},
+        InstanceBuilder,
+    };
+
+    #[test]
+    fn coerce_part() {
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Folder").with_child(
+                InstanceBuilder::new("Part")
+                    .with_property("Transparency", 0.5f64)
+                    .with_property("Color", Color3uint8::new(255, 0, 0))
+                    .with_property("Anchored", true)
+                    .with_property("NotARealProperty", 1.0f64),
+            ),
+        );
+
+        coerce_types(&mut dom, rbx_reflection_database::get());
+
+        let part = dom.get_by_ref(dom.root().children()[0]).unwrap();
+        assert_eq!(
+            part.properties.get("Transparency"),
+            Some(&Variant::Float32(0.5))
+        );
+        assert_eq!(
+            part.properties.get("Color"),
+            Some(&Variant::Color3(Color3::new(1.0, 0.0, 0.0)))
+        );
+        assert_eq!(part.properties.get("Anchored"), Some(&Variant::Bool(true)));
+        assert_eq!(
+            part.properties.get("NotARealProperty"),
+            Some(&Variant::Float64(1.0))
+        );
+    }
+
+    #[test]
+    fn leave_unconvertible() {
+        let mut dom =
+            WeakDom::new(InstanceBuilder::new("Part").with_property("Transparency", "very"));
+
+        coerce_types(&mut dom, rbx_reflection_database::get());
+
+        assert_eq!(
+            dom.root().properties.get("Transparency"),
+            Some(&Variant::from("very"))
+        );
+    }
+
+    #[test]
+    fn promote_position_to_cframe() {
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Part").with_property("CFrame", Vector3::new(1.0, 2.0, 3.0)),
+        );
+
+        coerce_types(&mut dom, rbx_reflection_database::get());
+
+        assert_eq!(
+            dom.root().properties.get("CFrame"),
+            Some(&Variant::CFrame(CFrame::new(
+                Vector3::new(1.0, 2.0, 3.0),
+                Matrix3::identity()
+            )))
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use rbx_dom_weak::{InstanceBuilder, WeakDom};
+use rbx_reflection::ReflectionDatabase;
+
+/// The services that Roblox Studio creates in every new place, in the order
+/// they appear in the Explorer.
+const DEFAULT_SERVICES: &[&str] = &[
+    "Workspace",
+    "Players",
+    "Lighting",
+    "ReplicatedFirst",
+    "ReplicatedStorage",
+    "ServerScriptService",
+    "ServerStorage",
+    "StarterGui",
+    "StarterPack",
+    "StarterPlayer",
+    "Teams",
+    "SoundService",
+    "Chat",
+    "LocalizationService",
+    "TestService",
+];
+
+/// Creates a new `WeakDom` containing a `DataModel` with the services that
+/// Roblox Studio creates in every new place, like `Workspace` and
+/// `ReplicatedStorage`.
+///
+/// Only classes that the given reflection database knows about and tags as
+/// services are created. Services have no properties set, so they take on
+/// their default values when the place is opened.
+pub fn default_data_model(database: &ReflectionDatabase) -> WeakDom {
+    let services = DEFAULT_SERVICES
+        .iter()
+        .filter(|&&class_name| is_service(database, class_name))
+        .map(|&class_name| InstanceBuilder::new(class_name));
+
+    WeakDom::new(InstanceBuilder::new("DataModel").with_children(services))
+}
+
+fn is_service(database: &ReflectionDatabase, class_name: &str) -> bool {
+    database
+        .classes
+        .get(class_name)
+        .map_or(false, |class| class.is_service())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_services() {
+        let dom = default_data_model(rbx_reflection_database::get());
+        let root = dom.root();
+
+        assert_eq!(root.class, "DataModel");
+
+        let children: Vec<_> = root
+            .children()
+            .iter()
+            .map(|&referent| dom.get_by_ref(referent).unwrap())
+            .collect();
+
+        for class_name in &["Workspace", "ReplicatedStorage"] {
+            let service = children
+                .iter()
+                .find(|instance| instance.class == *class_name)
+                .unwrap_or_else(|| panic!("{} was not created", class_name));
+
+            assert_eq!(service.name, *class_name);
+        }
+
+        assert_eq!(children.len(), DEFAULT_SERVICES.len());
+    }
+}
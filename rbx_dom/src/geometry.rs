@@ -0,0 +1,219 @@
+use rbx_dom_weak::{
+    types::{Ref, Variant, Vector3},
+    Instance, WeakDom,
+};
+
+/// Computes the world-space bounding box of the instance with the given
+/// referent and all of its descendants, returned as its minimum and maximum
+/// corners.
+///
+/// Every instance with a `Size` and either a `CFrame` or a `Position`, like a
+/// `BasePart`, contributes its rotated box. Other instances are skipped. If
+/// none of the instances have a position, `None` is returned.
+///
+/// ## Panics
+/// Panics if `referent` does not refer to an instance in the DOM.
+pub fn bounding_box(dom: &WeakDom, referent: Ref) -> Option<(Vector3, Vector3)> {
+    let root = dom.get_by_ref(referent).unwrap_or_else(|| {
+        panic!("cannot get the bounding box of an instance that does not exist")
+    });
+
+    let mut bounds = std::iter::once(root)
+        .chain(
+            dom.descendants_with_depth(referent)
+                .map(|(_, instance)| instance),
+        )
+        .filter_map(instance_bounds);
+
+    let first = bounds.next()?;
+
+    Some(
+        bounds.fold(first, |(min, max), (instance_min, instance_max)| {
+            (
+                min.component_min(instance_min),
+                max.component_max(instance_max),
+            )
+        }),
+    )
+}
+
+/// Finds the instance with the given referent and any of its descendants whose
+/// position is inside the axis-aligned box with the given center and size,
+/// including its edges.
+///
+/// Only instances with a `Size` and either a `CFrame` or a `Position`, like a
+/// `BasePart`, are considered. Other instances, like `Attachment`s, are
+/// skipped.
+///
+/// ## Panics
+/// Panics if `referent` does not refer to an instance in the DOM.
+pub fn query_region(dom: &WeakDom, referent: Ref, center: Vector3, size: Vector3) -> Vec<Ref> {
+    let root = dom.get_by_ref(referent).unwrap_or_else(|| {
+        panic!("cannot query the region around an instance that does not exist")
+    });
+
+    let half_size = size * 0.5;
+    let min = center - half_size;
+    let max = center + half_size;
+
+    std::iter::once(root)
+        .chain(
+            dom.descendants_with_depth(referent)
+                .map(|(_, instance)| instance),
+        )
+        .filter(|instance| {
+            part_position(instance).map_or(false, |position| {
+                (min.x..=max.x).contains(&position.x)
+                    && (min.y..=max.y).contains(&position.y)
+                    && (min.z..=max.z).contains(&position.z)
+            })
+        })
+        .map(|instance| instance.referent())
+        .collect()
+}
+
+fn part_position(instance: &Instance) -> Option<Vector3> {
+    match instance.properties.get("Size") {
+        Some(Variant::Vector3(_)) => {}
+        _ => return None,
+    }
+
+    match (
+        instance.properties.get("CFrame"),
+        instance.properties.get("Position"),
+    ) {
+        (Some(Variant::CFrame(cframe)), _) => Some(cframe.position),
+        (_, Some(Variant::Vector3(position))) => Some(*position),
+        _ => None,
+    }
+}
+
+fn instance_bounds(instance: &Instance) -> Option<(Vector3, Vector3)> {
+    let size = match instance.properties.get("Size") {
+        Some(Variant::Vector3(size)) => *size,
+        _ => return None,
+    };
+    let half_size = size * 0.5;
+
+    let (center, extents) = match (
+        instance.properties.get("CFrame"),
+        instance.properties.get("Position"),
+    ) {
+        (Some(Variant::CFrame(cframe)), _) => {
+            // Each row of the rotation matrix tells how much every local axis
+            // contributes to a world axis.
+            let extent = |row: Vector3| {
+                row.x.abs() * half_size.x + row.y.abs() * half_size.y + row.z.abs() * half_size.z
+            };
+            let orientation = cframe.orientation;
+
+            (
+                cframe.position,
+                Vector3::new(
+                    extent(orientation.x),
+                    extent(orientation.y),
+                    extent(orientation.z),
+                ),
+            )
+        }
+        (_, Some(Variant::Vector3(position))) => (*position, half_size),
+        _ => return None,
+    };
+
+    Some((center - extents, center + extents))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use rbx_dom_weak::{
+        types::{CFrame, Matrix3},
+        InstanceBuilder,
+    };
+
+    #[test]
+    fn two_parts() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Model")
+                .with_child(
+                    InstanceBuilder::new("Part")
+                        .with_property(
+                            "CFrame",
+                            CFrame::new(Vector3::new(0.0, 0.0, 0.0), Matrix3::identity()),
+                        )
+                        .with_property("Size", Vector3::new(2.0, 2.0, 2.0)),
+                )
+                .with_child(
+                    InstanceBuilder::new("Part")
+                        .with_property(
+                            "CFrame",
+                            CFrame::new(
+                                Vector3::new(10.0, 5.0, 0.0),
+                                // Rotated 90 degrees around the Y axis, which
+                                // swaps the X and Z extents.
+                                Matrix3::new(
+                                    Vector3::new(0.0, 0.0, 1.0),
+                                    Vector3::new(0.0, 1.0, 0.0),
+                                    Vector3::new(-1.0, 0.0, 0.0),
+                                ),
+                            ),
+                        )
+                        .with_property("Size", Vector3::new(4.0, 2.0, 8.0)),
+                )
+                .with_child(InstanceBuilder::new("Folder")),
+        );
+
+        assert_eq!(
+            bounding_box(&dom, dom.root_ref()),
+            Some((Vector3::new(-1.0, -1.0, -2.0), Vector3::new(14.0, 6.0, 2.0)))
+        );
+    }
+
+    #[test]
+    fn no_parts() {
+        let dom =
+            WeakDom::new(InstanceBuilder::new("Folder").with_child(InstanceBuilder::new("Model")));
+
+        assert_eq!(bounding_box(&dom, dom.root_ref()), None);
+    }
+
+    #[test]
+    fn region() {
+        let part_at = |x: f32, y: f32, z: f32| {
+            InstanceBuilder::new("Part")
+                .with_property(
+                    "CFrame",
+                    CFrame::new(Vector3::new(x, y, z), Matrix3::identity()),
+                )
+                .with_property("Size", Vector3::new(1.0, 1.0, 1.0))
+        };
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Workspace"));
+        let inside = dom.insert(dom.root_ref(), part_at(1.0, 1.0, 1.0));
+        let model = dom.insert(dom.root_ref(), InstanceBuilder::new("Model"));
+        let nested = dom.insert(model, part_at(-2.0, 0.0, 2.0));
+        dom.insert(dom.root_ref(), part_at(10.0, 0.0, 0.0));
+        dom.insert(dom.root_ref(), part_at(0.0, 3.0, 0.0));
+        dom.insert(
+            inside,
+            InstanceBuilder::new("Attachment").with_property(
+                "CFrame",
+                CFrame::new(Vector3::new(0.0, 0.0, 0.0), Matrix3::identity()),
+            ),
+        );
+
+        let found: HashSet<_> = query_region(
+            &dom,
+            dom.root_ref(),
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(4.0, 4.0, 4.0),
+        )
+        .into_iter()
+        .collect();
+
+        assert_eq!(found, [inside, nested].iter().copied().collect());
+    }
+}
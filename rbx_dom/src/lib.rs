@@ -0,0 +1,73 @@
+//! rbx_dom bundles together [rbx_dom_weak] and the file format crates that
+//! operate on it, [rbx_xml] and [rbx_binary]. Depending on this crate instead
+//! of each of them individually guarantees that every format works with the
+//! same [`WeakDom`] type.
+//!
+//! Each format lives in its own module and can be disabled by turning off its
+//! feature: `xml` for [`xml`] and `binary` for [`binary`]. Both are enabled by
+//! default. When both are enabled, [`decode_auto`] can decode a file in either
+//! format by detecting which one it is.
+#![cfg_attr(
+    all(feature = "xml", feature = "binary"),
+    doc = r#"
+```
+use rbx_dom::prelude::*;
+
+let dom = WeakDom::new(InstanceBuilder::new("Folder"));
+
+let mut binary = Vec::new();
+rbx_dom::binary::to_writer(&mut binary, &dom, &[dom.root_ref()])?;
+
+let mut xml = Vec::new();
+rbx_dom::xml::to_writer_default(&mut xml, &dom, &[dom.root_ref()])?;
+# Ok::<(), Box<dyn std::error::Error>>(())
+```
+"#
+)]
+//!
+//! [rbx_dom_weak]: https://docs.rs/rbx_dom_weak
+//! [rbx_xml]: https://docs.rs/rbx_xml
+//! [rbx_binary]: https://docs.rs/rbx_binary
+
+#![deny(missing_docs)]
+
+#[cfg(all(feature = "xml", feature = "binary"))]
+mod auto;
+mod coercion;
+mod data_model;
+mod geometry;
+mod migration;
+mod reflection;
+mod scripts;
+mod validation;
+
+#[cfg(all(feature = "xml", feature = "binary"))]
+pub use crate::auto::{decode_auto, decode_auto_path, DecodeError};
+
+pub use crate::{
+    coercion::coerce_types,
+    data_model::default_data_model,
+    geometry::{bounding_box, query_region},
+    migration::migrate_properties,
+    reflection::non_default_properties,
+    scripts::collect_sources,
+    validation::{validate, ValidationError},
+};
+
+pub use rbx_dom_weak::{types, DomViewer, Instance, InstanceBuilder, ViewedInstance, WeakDom};
+
+/// Roblox's XML model and place formats, rbxmx and rbxlx, from rbx_xml.
+#[cfg(feature = "xml")]
+pub use rbx_xml as xml;
+
+/// Roblox's binary model and place formats, rbxm and rbxl, from rbx_binary.
+#[cfg(feature = "binary")]
+pub use rbx_binary as binary;
+
+/// Re-exports the types that most users of rbx_dom will need.
+pub mod prelude {
+    pub use crate::{
+        types::{Ref, Variant, VariantType},
+        Instance, InstanceBuilder, WeakDom,
+    };
+}
@@ -0,0 +1,126 @@
+use rbx_dom_weak::WeakDom;
+use rbx_reflection::ReflectionDatabase;
+
+use crate::{coercion::coerce_value, reflection::expected_type};
+
+/// Renames properties of every instance in the given `WeakDom` that the given
+/// reflection database knows under a different canonical name, like
+/// `BasePart.BrickColor` to `BasePart.Color` or `Part.size` to `Part.Size`.
+///
+/// Values are converted to the canonical property's type in the same way as
+/// [`coerce_types`][crate::coerce_types], so a legacy `BrickColor` becomes a
+/// `Color3`. Properties whose values can't be converted are left alone and
+/// logged as warnings. If an instance already has a value for the canonical
+/// property, that value is kept and the legacy property is removed.
+pub fn migrate_properties(dom: &mut WeakDom, database: &ReflectionDatabase) {
+    let mut to_visit = vec![dom.root_ref()];
+
+    while let Some(referent) = to_visit.pop() {
+        let instance = dom.get_by_ref_mut(referent).unwrap();
+        to_visit.extend_from_slice(instance.children());
+
+        let mut legacy_names: Vec<_> = instance
+            .properties
+            .keys()
+            .filter_map(|property_name| {
+                let descriptor =
+                    database.find_canonical_property(&instance.class, property_name)?;
+
+                if descriptor.name == property_name.as_str() {
+                    None
+                } else {
+                    Some((property_name.clone(), descriptor))
+                }
+            })
+            .collect();
+        legacy_names.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (legacy_name, descriptor) in legacy_names {
+            let canonical_name = descriptor.name.as_ref();
+
+            if instance.properties.contains_key(canonical_name) {
+                instance.properties.remove(&legacy_name);
+                continue;
+            }
+
+            let value = &instance.properties[&legacy_name];
+            let migrated = match expected_type(&descriptor.data_type) {
+                Some(expected_type) if value.ty() != expected_type => {
+                    coerce_value(value, expected_type)
+                }
+                _ => Some(value.clone()),
+            };
+
+            match migrated {
+                Some(migrated) => {
+                    instance.properties.remove(&legacy_name);
+                    instance
+                        .properties
+                        .insert(canonical_name.to_owned(), migrated);
+                }
+                None => log::warn!(
+                    "Could not migrate property {}.{} to {}",
+                    instance.class,
+                    legacy_name,
+                    canonical_name
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::{
+        types::{BrickColor, Color3, Variant, Vector3},
+        InstanceBuilder,
+    };
+
+    #[test]
+    fn migrate_legacy_names() {
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Part")
+                .with_property("BrickColor", BrickColor::White)
+                .with_property("size", Vector3::new(1.0, 2.0, 3.0))
+                .with_property("Anchored", true),
+        );
+
+        migrate_properties(&mut dom, rbx_reflection_database::get());
+
+        let part = dom.root();
+        assert_eq!(part.properties.len(), 3);
+        assert_eq!(
+            part.properties.get("Color"),
+            Some(&Variant::Color3(Color3::new(
+                242.0 / 255.0,
+                243.0 / 255.0,
+                243.0 / 255.0
+            )))
+        );
+        assert_eq!(
+            part.properties.get("Size"),
+            Some(&Variant::Vector3(Vector3::new(1.0, 2.0, 3.0)))
+        );
+        assert_eq!(part.properties.get("Anchored"), Some(&Variant::Bool(true)));
+    }
+
+    #[test]
+    fn keep_canonical_value() {
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Part")
+                .with_property("size", Vector3::new(1.0, 1.0, 1.0))
+                .with_property("Size", Vector3::new(4.0, 4.0, 4.0)),
+        );
+
+        migrate_properties(&mut dom, rbx_reflection_database::get());
+
+        let part = dom.root();
+        assert_eq!(part.properties.len(), 1);
+        assert_eq!(
+            part.properties.get("Size"),
+            Some(&Variant::Vector3(Vector3::new(4.0, 4.0, 4.0)))
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use rbx_dom_weak::{
+    types::{Variant, VariantType},
+    Instance,
+};
+use rbx_reflection::{DataType, ReflectionDatabase};
+
+/// Returns the properties of the given instance whose values are different
+/// from the default value for the instance's class.
+///
+/// Properties that don't have a default value in the reflection database,
+/// including properties that it doesn't know about, are always returned. This
+/// is useful for writing the smallest possible description of an instance,
+/// like a patch or a compact file.
+pub fn non_default_properties<'a>(
+    instance: &'a Instance,
+    database: &ReflectionDatabase,
+) -> HashMap<&'a str, &'a Variant> {
+    instance
+        .properties
+        .iter()
+        .filter(|(name, value)| {
+            database.find_default_property(&instance.class, name) != Some(*value)
+        })
+        .map(|(name, value)| (name.as_str(), value))
+        .collect()
+}
+
+/// Returns the type that values of a property with the given data type are
+/// expected to have, if it is one that can be held by a `Variant`.
+pub(crate) fn expected_type(data_type: &DataType) -> Option<VariantType> {
+    match data_type {
+        DataType::Value(ty) => Some(*ty),
+        DataType::Enum(_) => Some(VariantType::Enum),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::{InstanceBuilder, WeakDom};
+
+    #[test]
+    fn part_non_default_properties() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Part")
+                .with_property("Anchored", true)
+                .with_property("CanCollide", true)
+                .with_property("Locked", false)
+                .with_property("Transparency", 0.0f32)
+                .with_property("NotARealProperty", 1.0f32),
+        );
+
+        let mut names: Vec<_> = non_default_properties(dom.root(), rbx_reflection_database::get())
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        names.sort_unstable();
+
+        assert_eq!(names, ["Anchored", "NotARealProperty"]);
+    }
+}
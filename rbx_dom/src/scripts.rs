@@ -0,0 +1,98 @@
+use rbx_dom_weak::{
+    types::{Ref, Variant},
+    WeakDom,
+};
+use rbx_reflection::ReflectionDatabase;
+
+/// The classes that hold Luau source code, used for classes that the
+/// reflection database doesn't know about.
+const SCRIPT_CLASSES: &[&str] = &["Script", "LocalScript", "ModuleScript"];
+
+/// Finds every script in the given `WeakDom`, returning its referent and its
+/// `Source` property. Scripts without a `Source` property have no code, so
+/// their source is empty.
+///
+/// An instance is a script if the reflection database says that its class
+/// inherits from `LuaSourceContainer`. Classes that the database doesn't know
+/// about are checked against a list of the script classes instead.
+///
+/// Scripts are returned in breadth-first order from the root.
+pub fn collect_sources(dom: &WeakDom, database: &ReflectionDatabase) -> Vec<(Ref, String)> {
+    dom.iter_topological(dom.root_ref())
+        .filter(|instance| is_script(database, &instance.class))
+        .map(|instance| {
+            let source = match instance.properties.get("Source") {
+                Some(Variant::String(source)) => source.clone(),
+                _ => String::new(),
+            };
+
+            (instance.referent(), source)
+        })
+        .collect()
+}
+
+fn is_script(database: &ReflectionDatabase, class_name: &str) -> bool {
+    let mut current_class = match database.classes.get(class_name) {
+        Some(class) => class,
+        None => return SCRIPT_CLASSES.contains(&class_name),
+    };
+
+    loop {
+        if current_class.name == "LuaSourceContainer" {
+            return true;
+        }
+
+        current_class = match current_class
+            .superclass
+            .as_ref()
+            .and_then(|superclass| database.classes.get(superclass.as_ref()))
+        {
+            Some(superclass) => superclass,
+            None => return false,
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::InstanceBuilder;
+
+    #[test]
+    fn scripts_only() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let script_ref = dom.insert(
+            dom.root_ref(),
+            InstanceBuilder::new("Script").with_property("Source", "print('Hello')"),
+        );
+        let module_ref = dom.insert(script_ref, InstanceBuilder::new("ModuleScript"));
+        dom.insert(
+            dom.root_ref(),
+            InstanceBuilder::new("StringValue").with_property("Value", "print('Not code')"),
+        );
+
+        let sources = collect_sources(&dom, rbx_reflection_database::get());
+
+        assert_eq!(
+            sources,
+            vec![
+                (script_ref, "print('Hello')".to_owned()),
+                (module_ref, String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_classes() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(InstanceBuilder::new("LocalScript").with_property("Source", "return")),
+        );
+
+        let sources = collect_sources(&dom, &ReflectionDatabase::new());
+
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].1, "return");
+    }
+}
@@ -0,0 +1,179 @@
+use rbx_dom_weak::{
+    types::{Ref, VariantType},
+    WeakDom,
+};
+use rbx_reflection::ReflectionDatabase;
+use thiserror::Error;
+
+use crate::reflection::expected_type;
+
+/// A problem with a property found by [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// The property isn't known by the reflection database for the instance's
+    /// class.
+    #[error("Property {class_name}.{property_name} is unknown")]
+    UnknownProperty {
+        /// The referent of the instance the property is on.
+        referent: Ref,
+        /// The class of the instance the property is on.
+        class_name: String,
+        /// The name of the property.
+        property_name: String,
+    },
+
+    /// The property's value has a different type than the reflection database
+    /// expects.
+    #[error(
+        "Property {class_name}.{property_name} should be of type {expected_type}, but it was of type {actual_type}"
+    )]
+    TypeMismatch {
+        /// The referent of the instance the property is on.
+        referent: Ref,
+        /// The class of the instance the property is on.
+        class_name: String,
+        /// The name of the property.
+        property_name: String,
+        /// The type that the reflection database expects.
+        expected_type: VariantType,
+        /// The type of the property's value.
+        actual_type: VariantType,
+    },
+}
+
+/// Checks every property of every instance in the given `WeakDom` against the
+/// given reflection database, returning any problems that were found. The
+/// `WeakDom` is not modified.
+///
+/// Instances are visited breadth-first starting from the root, and each
+/// instance's properties are checked in alphabetical order.
+///
+/// Values can have either the type of the property's canonical form or the
+/// type it is serialized as, like a `Color3uint8` for `BasePart.Color`, since
+/// decoders produce the latter.
+pub fn validate(dom: &WeakDom, database: &ReflectionDatabase) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut to_visit = vec![dom.root_ref()];
+
+    while !to_visit.is_empty() {
+        let mut next_to_visit = Vec::new();
+
+        for referent in to_visit {
+            let instance = dom.get_by_ref(referent).unwrap();
+            next_to_visit.extend_from_slice(instance.children());
+
+            let mut properties: Vec<_> = instance.properties.iter().collect();
+            properties.sort_unstable_by_key(|(name, _)| *name);
+
+            for (property_name, value) in properties {
+                let descriptor = database.find_canonical_property(&instance.class, property_name);
+
+                let descriptor = match descriptor {
+                    Some(descriptor) => descriptor,
+                    None => {
+                        errors.push(ValidationError::UnknownProperty {
+                            referent,
+                            class_name: instance.class.clone(),
+                            property_name: property_name.clone(),
+                        });
+                        continue;
+                    }
+                };
+
+                if let Some(expected_type) = expected_type(&descriptor.data_type) {
+                    let serialized_type = database.serialized_type(&instance.class, property_name);
+
+                    if value.ty() != expected_type && Some(value.ty()) != serialized_type {
+                        errors.push(ValidationError::TypeMismatch {
+                            referent,
+                            class_name: instance.class.clone(),
+                            property_name: property_name.clone(),
+                            expected_type,
+                            actual_type: value.ty(),
+                        });
+                    }
+                }
+            }
+        }
+
+        to_visit = next_to_visit;
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::InstanceBuilder;
+
+    #[test]
+    fn valid() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder").with_child(
+                InstanceBuilder::new("Part")
+                    .with_property("Transparency", 0.5f32)
+                    .with_property("Anchored", true),
+            ),
+        );
+
+        let errors = validate(&dom, rbx_reflection_database::get());
+        assert_eq!(errors, Vec::new());
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn decoded_part() {
+        // Part.Color is a Color3, but it's decoded from the Color3uint8 that it
+        // is serialized as.
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Part</string>
+                        <Color3uint8 name="Color3uint8">4294901760</Color3uint8>
+                        <bool name="Anchored">true</bool>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let dom = rbx_xml::from_str_default(document).unwrap();
+        let part = dom.get_by_ref(dom.root().children()[0]).unwrap();
+        assert_eq!(part.properties["Color"].ty(), VariantType::Color3uint8);
+
+        let errors = validate(&dom, rbx_reflection_database::get());
+        assert_eq!(errors, Vec::new());
+    }
+
+    #[test]
+    fn invalid() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Part")
+                .with_property("Transparency", 0.5f64)
+                .with_property("NotARealProperty", true),
+        );
+        let root_ref = dom.root_ref();
+
+        let errors = validate(&dom, rbx_reflection_database::get());
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::UnknownProperty {
+                    referent: root_ref,
+                    class_name: "Part".to_owned(),
+                    property_name: "NotARealProperty".to_owned(),
+                },
+                ValidationError::TypeMismatch {
+                    referent: root_ref,
+                    class_name: "Part".to_owned(),
+                    property_name: "Transparency".to_owned(),
+                    expected_type: VariantType::Float32,
+                    actual_type: VariantType::Float64,
+                },
+            ]
+        );
+    }
+}
@@ -1,8 +1,15 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap, HashSet, VecDeque},
+    fmt::Write,
+    hash::{Hash, Hasher},
+};
 
-use rbx_types::Ref;
+use rbx_types::{Content, Ref, Variant};
 
-use crate::instance::{Instance, InstanceBuilder};
+use crate::{
+    instance::{Instance, InstanceBuilder},
+    name_index::NameIndex,
+};
 
 /// Represents a DOM containing one or more Roblox instances.
 ///
@@ -20,9 +27,18 @@ pub struct WeakDom {
 impl WeakDom {
     /// Construct a new `WeakDom` described by the given [`InstanceBuilder`].
     pub fn new(builder: InstanceBuilder) -> WeakDom {
+        WeakDom::with_capacity(builder, 0)
+    }
+
+    /// Construct a new `WeakDom` described by the given [`InstanceBuilder`],
+    /// with room for at least `capacity` instances before needing to grow.
+    ///
+    /// This is useful when the number of instances that will end up in the
+    /// `WeakDom` is known ahead of time, like when decoding a file.
+    pub fn with_capacity(builder: InstanceBuilder, capacity: usize) -> WeakDom {
         let root_ref = builder.referent;
 
-        let mut instances = HashMap::new();
+        let mut instances = HashMap::with_capacity(capacity);
         instances.insert(
             root_ref,
             Instance {
@@ -32,6 +48,7 @@ impl WeakDom {
                 name: builder.name,
                 class: builder.class,
                 properties: builder.properties,
+                unknown_properties: HashMap::new(),
             },
         );
 
@@ -74,6 +91,438 @@ impl WeakDom {
         self.instances.get_mut(&referent)
     }
 
+    /// Tells whether an instance with the given referent is in the DOM.
+    pub fn contains(&self, referent: Ref) -> bool {
+        self.instances.contains_key(&referent)
+    }
+
+    /// Tells whether the instance with the referent `ancestor` is an ancestor
+    /// of the instance with the referent `descendant`. An instance is not its
+    /// own ancestor.
+    ///
+    /// Returns `false` if either instance is not in the DOM.
+    pub fn is_ancestor_of(&self, ancestor: Ref, descendant: Ref) -> bool {
+        if !self.instances.contains_key(&ancestor) {
+            return false;
+        }
+
+        let mut current = match self.instances.get(&descendant) {
+            Some(instance) => instance.parent,
+            None => return false,
+        };
+
+        while let Some(instance) = self.instances.get(&current) {
+            if current == ancestor {
+                return true;
+            }
+
+            current = instance.parent;
+        }
+
+        false
+    }
+
+    /// Finds every Ref property in the DOM that points to an instance that
+    /// isn't in the DOM, like a weld to a part that was never loaded. Null
+    /// refs are not included. `Content` properties that point to an object
+    /// are checked the same way.
+    ///
+    /// Each result contains the referent of the instance that owns the
+    /// property, the property's name, and the missing referent. Instances are
+    /// visited breadth-first from the root, and their properties are visited
+    /// in alphabetical order.
+    pub fn dangling_refs(&self) -> Vec<(Ref, String, Ref)> {
+        let mut dangling = Vec::new();
+        let mut to_visit = VecDeque::new();
+        to_visit.push_back(self.root_ref);
+
+        while let Some(referent) = to_visit.pop_front() {
+            let instance = &self.instances[&referent];
+            to_visit.extend(instance.children.iter().copied());
+
+            let mut properties: Vec<_> = instance.properties.iter().collect();
+            properties.sort_unstable_by_key(|(name, _)| *name);
+
+            for (name, value) in properties {
+                let target = match value {
+                    Variant::Ref(target) => *target,
+                    Variant::Content(content) => match content.as_object() {
+                        Some(target) => target,
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+
+                if target.is_some() && !self.instances.contains_key(&target) {
+                    dangling.push((referent, name.clone(), target));
+                }
+            }
+        }
+
+        dangling
+    }
+
+    /// Returns the referent of every instance with an empty name. Roblox
+    /// Studio behaves confusingly with instances like these, so they're worth
+    /// fixing before a DOM is saved.
+    ///
+    /// Instances are visited breadth-first from the root.
+    pub fn validate_names(&self) -> Vec<Ref> {
+        let mut unnamed = Vec::new();
+        let mut to_visit = VecDeque::new();
+        to_visit.push_back(self.root_ref);
+
+        while let Some(referent) = to_visit.pop_front() {
+            let instance = &self.instances[&referent];
+            to_visit.extend(instance.children.iter().copied());
+
+            if instance.name.is_empty() {
+                unnamed.push(referent);
+            }
+        }
+
+        unnamed
+    }
+
+    /// Tells whether this DOM has the same structure as `other`, ignoring the
+    /// referents of the instances in them. This is useful for comparing two
+    /// loads of the same file, since referents are different every time.
+    ///
+    /// Instances are equal if they have the same class, name, properties,
+    /// unknown properties, and children, in the same order. Ref properties are equal if both are null,
+    /// or if they point to instances in the same position in both DOMs. Refs
+    /// to instances outside of either DOM must have the same value. `Content`
+    /// properties that point to objects are compared the same way. Other
+    /// values are compared with [`Variant::bitwise_eq`], so a DOM holding a
+    /// NaN float is still equal to itself.
+    pub fn structural_eq(&self, other: &WeakDom) -> bool {
+        let mut ref_map = HashMap::new();
+        let mut pairs = Vec::new();
+        let mut to_visit = vec![(self.root_ref, other.root_ref)];
+
+        while let Some((referent, other_referent)) = to_visit.pop() {
+            let instance = &self.instances[&referent];
+            let other_instance = &other.instances[&other_referent];
+
+            if instance.class != other_instance.class
+                || instance.name != other_instance.name
+                || instance.properties.len() != other_instance.properties.len()
+                || instance.unknown_properties != other_instance.unknown_properties
+                || instance.children.len() != other_instance.children.len()
+            {
+                return false;
+            }
+
+            ref_map.insert(referent, other_referent);
+            pairs.push((instance, other_instance));
+            to_visit.extend(
+                instance
+                    .children
+                    .iter()
+                    .copied()
+                    .zip(other_instance.children.iter().copied()),
+            );
+        }
+
+        let same_target = |target: Ref, other_target: Ref| match ref_map.get(&target) {
+            Some(mapped) => *mapped == other_target,
+            None => target == other_target,
+        };
+
+        pairs.into_iter().all(|(instance, other_instance)| {
+            instance.properties.iter().all(|(name, value)| {
+                match (value, other_instance.properties.get(name)) {
+                    (Variant::Ref(target), Some(Variant::Ref(other_target))) => {
+                        same_target(*target, *other_target)
+                    }
+                    (Variant::Content(content), Some(Variant::Content(other_content))) => {
+                        match (content.as_object(), other_content.as_object()) {
+                            (Some(target), Some(other_target)) => same_target(target, other_target),
+                            _ => content == other_content,
+                        }
+                    }
+                    (value, Some(other_value)) => value.bitwise_eq(other_value),
+                    (_, None) => false,
+                }
+            })
+        })
+    }
+
+    /// Returns a hash of the instance with the given referent and all of its
+    /// descendants, built from [`Instance::content_hash`] and the order of
+    /// each instance's children.
+    ///
+    /// Like [`WeakDom::structural_eq`], this doesn't depend on referents, so an
+    /// unchanged subtree hashes the same across loads. Ref properties pointing
+    /// inside the subtree are hashed by the position of their target, and Refs
+    /// pointing outside of it only hash by whether they're null. `Content`
+    /// properties that point to an instance are hashed the same way.
+    ///
+    /// ## Panics
+    /// Panics if `referent` does not refer to an instance in the DOM.
+    pub fn subtree_hash(&self, referent: Ref) -> u64 {
+        let positions: HashMap<Ref, usize> = self
+            .iter_topological(referent)
+            .enumerate()
+            .map(|(position, instance)| (instance.referent, position))
+            .collect();
+
+        let mut state = DefaultHasher::new();
+
+        for instance in self.iter_topological(referent) {
+            crate::hash::hash_instance(instance, &mut state, |target| {
+                positions.get(&target).copied()
+            });
+            instance.children.len().hash(&mut state);
+        }
+
+        state.finish()
+    }
+
+    /// Builds a [`NameIndex`] of every instance in the DOM, which can find
+    /// children by name without scanning through their siblings.
+    ///
+    /// The index is not updated when the DOM changes, so it should be rebuilt
+    /// after any mutation that adds, removes, moves, or renames instances.
+    pub fn build_name_index(&self) -> NameIndex {
+        NameIndex::new(self)
+    }
+
+    /// Replaces all of the properties of the instance with the given referent,
+    /// returning its previous properties. The instance's name and class are
+    /// left untouched.
+    ///
+    /// Returns `None` and leaves the DOM unchanged if the instance does not
+    /// exist.
+    pub fn swap_properties(
+        &mut self,
+        referent: Ref,
+        properties: HashMap<String, Variant>,
+    ) -> Option<HashMap<String, Variant>> {
+        let instance = self.instances.get_mut(&referent)?;
+
+        Some(std::mem::replace(&mut instance.properties, properties))
+    }
+
+    /// Removes the property with the given name from the instance with the
+    /// given referent, returning its value.
+    ///
+    /// Returns `None` if the instance does not exist or does not have the
+    /// property.
+    pub fn remove_property(&mut self, referent: Ref, name: &str) -> Option<Variant> {
+        self.instances.get_mut(&referent)?.properties.remove(name)
+    }
+
+    /// Tells whether the instance with the given referent has a property with
+    /// the given name.
+    ///
+    /// Returns `false` if the instance does not exist.
+    pub fn has_property(&self, referent: Ref, name: &str) -> bool {
+        self.instances
+            .get(&referent)
+            .map_or(false, |instance| instance.properties.contains_key(name))
+    }
+
+    /// Returns the name of every property that is set on at least one
+    /// instance in the DOM.
+    ///
+    /// This is useful for finding properties that a file or tool added
+    /// unexpectedly.
+    pub fn all_property_names(&self) -> BTreeSet<String> {
+        self.instances
+            .values()
+            .flat_map(|instance| instance.properties.keys().cloned())
+            .collect()
+    }
+
+    /// Returns the name of every property that is set on at least one instance
+    /// of the given class in the DOM.
+    pub fn property_names_for_class(&self, class: &str) -> BTreeSet<String> {
+        self.instances
+            .values()
+            .filter(|instance| instance.class == class)
+            .flat_map(|instance| instance.properties.keys().cloned())
+            .collect()
+    }
+
+    /// Changes the name of the instance with the given referent.
+    ///
+    /// Returns `false` and leaves the DOM unchanged if the instance does not
+    /// exist.
+    pub fn rename<S: Into<String>>(&mut self, referent: Ref, name: S) -> bool {
+        match self.instances.get_mut(&referent) {
+            Some(instance) => {
+                instance.name = name.into();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Changes the class of the instance with the given referent. Its name and
+    /// properties are left untouched.
+    ///
+    /// Returns `false` and leaves the DOM unchanged if the instance does not
+    /// exist.
+    pub fn set_class<S: Into<String>>(&mut self, referent: Ref, class: S) -> bool {
+        match self.instances.get_mut(&referent) {
+            Some(instance) => {
+                instance.class = class.into();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns an iterator over all of the descendants of the instance with the
+    /// given referent, along with their depth relative to it. Immediate
+    /// children have a depth of 1.
+    ///
+    /// Descendants are visited depth-first, with each instance appearing
+    /// before its children, which makes this useful for printing a tree.
+    ///
+    /// ## Panics
+    /// Panics if `referent` does not refer to an instance in the DOM.
+    pub fn descendants_with_depth(
+        &self,
+        referent: Ref,
+    ) -> impl Iterator<Item = (usize, &Instance)> + '_ {
+        let instance = self
+            .instances
+            .get(&referent)
+            .unwrap_or_else(|| panic!("cannot get descendants of an instance that does not exist"));
+
+        let mut to_visit: Vec<(usize, Ref)> = instance
+            .children
+            .iter()
+            .rev()
+            .map(|&child| (1, child))
+            .collect();
+
+        std::iter::from_fn(move || {
+            let (depth, referent) = to_visit.pop()?;
+            let instance = &self.instances[&referent];
+
+            to_visit.extend(
+                instance
+                    .children
+                    .iter()
+                    .rev()
+                    .map(|&child| (depth + 1, child)),
+            );
+
+            Some((depth, instance))
+        })
+    }
+
+    /// Returns an iterator over the instance with the given referent and all of
+    /// its descendants, in breadth-first order.
+    ///
+    /// Every instance is guaranteed to appear after its parent, which is what
+    /// serializers that need to write parents before children depend on.
+    ///
+    /// ## Panics
+    /// Panics if `referent` does not refer to an instance in the DOM.
+    pub fn iter_topological(&self, referent: Ref) -> impl Iterator<Item = &Instance> + '_ {
+        if !self.instances.contains_key(&referent) {
+            panic!("cannot iterate over an instance that does not exist");
+        }
+
+        let mut to_visit = VecDeque::new();
+        to_visit.push_back(referent);
+
+        std::iter::from_fn(move || {
+            let referent = to_visit.pop_front()?;
+            let instance = &self.instances[&referent];
+            to_visit.extend(instance.children.iter().copied());
+
+            Some(instance)
+        })
+    }
+
+    /// Returns a human-readable listing of the instance with the given referent
+    /// and all of its descendants, one per line, like `Part "Handle"`. Each
+    /// instance is indented by two spaces per level of depth.
+    ///
+    /// If `include_properties` is true, each instance's properties are listed
+    /// below it in alphabetical order, like `.Anchored = Bool(true)`.
+    ///
+    /// This is meant for debugging; the format is not stable.
+    ///
+    /// ## Panics
+    /// Panics if `referent` does not refer to an instance in the DOM.
+    pub fn dump_tree(&self, referent: Ref, include_properties: bool) -> String {
+        let instance = self
+            .instances
+            .get(&referent)
+            .unwrap_or_else(|| panic!("cannot dump an instance that does not exist"));
+
+        let mut output = String::new();
+
+        for (depth, instance) in
+            std::iter::once((0, instance)).chain(self.descendants_with_depth(referent))
+        {
+            let indent = "  ".repeat(depth);
+            writeln!(output, "{}{} {:?}", indent, instance.class, instance.name).unwrap();
+
+            if include_properties {
+                let mut properties: Vec<_> = instance.properties.iter().collect();
+                properties.sort_unstable_by_key(|(name, _)| *name);
+
+                for (name, value) in properties {
+                    writeln!(output, "{}  .{} = {:?}", indent, name, value).unwrap();
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Calls the given function with a reference to every instance in the DOM,
+    /// spreading the work across rayon's thread pool. Instances are visited in
+    /// no particular order.
+    ///
+    /// This is only available with the `rayon` feature enabled.
+    #[cfg(feature = "rayon")]
+    pub fn par_for_each<F>(&self, callback: F)
+    where
+        F: Fn(&Instance) + Sync + Send,
+    {
+        use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+        self.instances
+            .par_iter()
+            .for_each(|(_, instance)| callback(instance));
+    }
+
+    /// Calls the given function with a _mutable_ reference to every descendant
+    /// of the instance with the given referent, in the same order as
+    /// [`WeakDom::descendants_with_depth`].
+    ///
+    /// ## Panics
+    /// Panics if `referent` does not refer to an instance in the DOM.
+    pub fn for_each_descendant_mut<F>(&mut self, referent: Ref, mut callback: F)
+    where
+        F: FnMut(&mut Instance),
+    {
+        let instance = self
+            .instances
+            .get(&referent)
+            .unwrap_or_else(|| panic!("cannot get descendants of an instance that does not exist"));
+
+        let mut to_visit: Vec<Ref> = instance.children.iter().rev().copied().collect();
+
+        while let Some(referent) = to_visit.pop() {
+            let instance = self.instances.get_mut(&referent).unwrap();
+
+            // The callback can't change the children of an instance, so it's
+            // fine to read them after it runs.
+            callback(instance);
+
+            to_visit.extend(instance.children.iter().rev());
+        }
+    }
+
     /// Insert a new instance into the DOM with the given parent.
     ///
     /// ## Panics
@@ -90,6 +539,7 @@ impl WeakDom {
                 name: builder.name,
                 class: builder.class,
                 properties: builder.properties,
+                unknown_properties: HashMap::new(),
             },
         );
 
@@ -107,6 +557,74 @@ impl WeakDom {
         referent
     }
 
+    /// Insert many instances into the DOM at once, each paired with the
+    /// referent of its parent.
+    ///
+    /// A parent can either already be in the DOM or be one of the other
+    /// instances being inserted, so the instances can be in any order. Siblings
+    /// are inserted in the order that they appear in `records`.
+    ///
+    /// If any of the instances have a parent that is neither in the DOM nor
+    /// being inserted, nothing is inserted and their referents are returned as
+    /// an error.
+    pub fn insert_many(&mut self, records: Vec<(InstanceBuilder, Ref)>) -> Result<(), Vec<Ref>> {
+        let mut records_by_parent: HashMap<Ref, Vec<usize>> = HashMap::new();
+        for (index, (_, parent_ref)) in records.iter().enumerate() {
+            records_by_parent
+                .entry(*parent_ref)
+                .or_default()
+                .push(index);
+        }
+
+        // Starting from the records whose parents are already in the DOM, find
+        // every record that can be reached. Any others are orphans.
+        let mut to_visit: VecDeque<usize> = records
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, parent_ref))| self.instances.contains_key(parent_ref))
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut order = Vec::with_capacity(records.len());
+        let mut reached = HashSet::with_capacity(records.len());
+
+        while let Some(index) = to_visit.pop_front() {
+            if !reached.insert(index) {
+                continue;
+            }
+
+            order.push(index);
+
+            let mut builders = vec![&records[index].0];
+            while let Some(builder) = builders.pop() {
+                if let Some(children) = records_by_parent.get(&builder.referent) {
+                    to_visit.extend(children);
+                }
+
+                builders.extend(&builder.children);
+            }
+        }
+
+        if reached.len() < records.len() {
+            return Err(records
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !reached.contains(index))
+                .map(|(_, (builder, _))| builder.referent)
+                .collect());
+        }
+
+        let mut records: Vec<Option<(InstanceBuilder, Ref)>> =
+            records.into_iter().map(Some).collect();
+
+        for index in order {
+            let (builder, parent_ref) = records[index].take().unwrap();
+            self.insert(parent_ref, builder);
+        }
+
+        Ok(())
+    }
+
     /// Destroy the instance with the given referent.
     ///
     /// ## Panics
@@ -137,19 +655,93 @@ impl WeakDom {
         }
     }
 
+    /// Remove every child of the instance with the given referent for which the
+    /// predicate returns `false`, along with all of its descendants. Children
+    /// that are kept retain their relative order.
+    ///
+    /// Each removed child is returned as the root of its own `WeakDom`, in the
+    /// order that the children appeared in the parent.
+    ///
+    /// ## Panics
+    /// Panics if `parent_ref` does not refer to an instance in the DOM.
+    pub fn retain_children<F>(&mut self, parent_ref: Ref, mut predicate: F) -> Vec<WeakDom>
+    where
+        F: FnMut(&Instance) -> bool,
+    {
+        let parent = self
+            .instances
+            .get(&parent_ref)
+            .unwrap_or_else(|| panic!("cannot retain children of an instance that does not exist"));
+
+        let to_remove: Vec<Ref> = parent
+            .children
+            .iter()
+            .copied()
+            .filter(|child_ref| !predicate(&self.instances[child_ref]))
+            .collect();
+
+        if to_remove.is_empty() {
+            return Vec::new();
+        }
+
+        // Looking children up in a set keeps this linear when most of a large
+        // parent's children are removed.
+        let removed: HashSet<Ref> = to_remove.iter().copied().collect();
+        let parent = self.instances.get_mut(&parent_ref).unwrap();
+        parent.children.retain(|child| !removed.contains(child));
+
+        to_remove
+            .into_iter()
+            .map(|referent| self.take_subtree(referent))
+            .collect()
+    }
+
+    /// Move the instance with the given referent and all of its descendants
+    /// into a new `WeakDom`, with that instance as its root.
+    ///
+    /// The caller is responsible for unlinking the instance from its parent's
+    /// list of children beforehand.
+    fn take_subtree(&mut self, referent: Ref) -> WeakDom {
+        let mut instances = HashMap::new();
+
+        let mut to_move = VecDeque::new();
+        to_move.push_back(referent);
+
+        while let Some(referent) = to_move.pop_front() {
+            let instance = self.instances.remove(&referent).unwrap();
+            to_move.extend(instance.children.iter().copied());
+            instances.insert(referent, instance);
+        }
+
+        instances.get_mut(&referent).unwrap().parent = Ref::none();
+
+        WeakDom {
+            instances,
+            root_ref: referent,
+        }
+    }
+
     /// Move the instance with the given referent to a new `WeakDom`, parenting
     /// it to the given ref. To move to within the same DOM, use
     /// [`WeakDom::transfer_within`].
     ///
     /// This function would be called `move`, but that's a Rust keyword!
     ///
+    /// Returns the referents of every instance that was moved, starting with
+    /// `referent` and followed by its descendants in breadth-first order.
+    ///
     /// ## Panics
     /// Panics if `referent` does not refer to an instance in `self` or if
     /// `dest_parent_ref` does not refer to an instance in `other_dom`.
     ///
     /// Will also panic if `referent` refers to the root instance in this
     /// `WeakDom`.
-    pub fn transfer(&mut self, referent: Ref, dest: &mut WeakDom, dest_parent_ref: Ref) {
+    pub fn transfer(
+        &mut self,
+        referent: Ref,
+        dest: &mut WeakDom,
+        dest_parent_ref: Ref,
+    ) -> Vec<Ref> {
         if referent == self.root_ref {
             panic!("cannot transfer the root instance of WeakDom");
         }
@@ -176,11 +768,14 @@ impl WeakDom {
         instance.parent = dest_parent_ref;
         dest.instances.insert(referent, instance);
 
+        let mut moved = vec![referent];
+
         // Transfer all of the descendants of the moving instance breadth-first.
         while let Some(referent) = to_move.pop_front() {
             let instance = self.instances.remove(&referent).unwrap();
             to_move.extend(instance.children.iter().copied());
             dest.instances.insert(referent, instance);
+            moved.push(referent);
         }
 
         // Finally, notify the new parent instance that their adoption is
@@ -189,6 +784,8 @@ impl WeakDom {
             panic!("cannot move an instance into an instance that does not exist")
         });
         dest_parent.children.push(referent);
+
+        moved
     }
 
     /// Move the instance with the given referent to a new parent within the
@@ -202,12 +799,17 @@ impl WeakDom {
     /// `self`.
     ///
     /// Will also panic if `referent` refers to the root instance in this
-    /// `WeakDom`.
+    /// `WeakDom`, or if `dest_parent_ref` is `referent` or one of its
+    /// descendants, since that would create a cycle.
     pub fn transfer_within(&mut self, referent: Ref, dest_parent_ref: Ref) {
         if referent == self.root_ref {
             panic!("cannot transfer the root instance of WeakDom");
         }
 
+        if dest_parent_ref == referent || self.is_ancestor_of(referent, dest_parent_ref) {
+            panic!("cannot move an instance into itself or its descendants, as that would create a cycle");
+        }
+
         let mut instance = self
             .instances
             .get_mut(&referent)
@@ -228,13 +830,201 @@ impl WeakDom {
             .unwrap_or_else(|| panic!("cannot move into an instance that does not exist"));
         dest_parent.children.push(referent);
     }
+
+    /// Replace the instance with the given referent and all of its descendants
+    /// with the contents of another `WeakDom`.
+    ///
+    /// The root of `replacement` takes over the referent, parent, and position
+    /// among its siblings of the instance being replaced, while the rest of
+    /// `replacement`'s instances keep their own referents. Ref properties that
+    /// pointed to the root of `replacement` are not rewritten. Those referents
+    /// may be the same as the ones of the instances being replaced, like when
+    /// replacing a subtree with an edited copy of itself.
+    ///
+    /// ## Panics
+    /// Panics if `referent` does not refer to an instance in the DOM, or if any
+    /// instance in `replacement` other than its root has the same referent as
+    /// an instance in this DOM that isn't a descendant of the one being
+    /// replaced.
+    pub fn replace_subtree(&mut self, referent: Ref, mut replacement: WeakDom) {
+        let instance = self
+            .instances
+            .get(&referent)
+            .unwrap_or_else(|| panic!("cannot replace an instance that does not exist"));
+
+        let parent_ref = instance.parent;
+
+        let mut descendants = HashSet::new();
+        let mut to_visit: VecDeque<Ref> = instance.children.iter().copied().collect();
+
+        while let Some(child_ref) = to_visit.pop_front() {
+            descendants.insert(child_ref);
+            to_visit.extend(&self.instances[&child_ref].children);
+        }
+
+        let mut new_root = replacement.instances.remove(&replacement.root_ref).unwrap();
+
+        let collides = replacement
+            .instances
+            .keys()
+            .any(|key| self.instances.contains_key(key) && !descendants.contains(key));
+
+        if collides {
+            panic!(
+                "cannot replace a subtree with instances whose referents are already in the DOM"
+            );
+        }
+
+        self.instances.remove(&referent);
+        for descendant_ref in &descendants {
+            self.instances.remove(descendant_ref);
+        }
+
+        new_root.referent = referent;
+        new_root.parent = parent_ref;
+
+        for child_ref in &new_root.children {
+            replacement.instances.get_mut(child_ref).unwrap().parent = referent;
+        }
+
+        self.instances.extend(replacement.instances);
+        self.instances.insert(referent, new_root);
+    }
+
+    /// Move every instance in `other` into this DOM, parenting the root of
+    /// `other` to the instance with the referent `parent_ref`. Returns the
+    /// referent of what was the root of `other`.
+    ///
+    /// If `remap_refs` is true, every merged instance is given a new referent,
+    /// and Ref properties and object `Content` properties pointing to instances
+    /// from `other` are rewritten to match. Ones pointing anywhere else are
+    /// left alone. This is
+    /// useful when combining DOMs that could share referents, like multiple
+    /// copies of the same model.
+    ///
+    /// ## Panics
+    /// Panics if `parent_ref` does not refer to an instance in the DOM, or if
+    /// `remap_refs` is false and any instance in `other` has the same referent
+    /// as an instance in this DOM.
+    pub fn merge(&mut self, other: WeakDom, parent_ref: Ref, remap_refs: bool) -> Ref {
+        if !self.instances.contains_key(&parent_ref) {
+            panic!("cannot merge into an instance that does not exist");
+        }
+
+        let WeakDom {
+            mut instances,
+            root_ref,
+        } = other;
+
+        let root_ref = if remap_refs {
+            let new_refs = fresh_refs(&instances);
+
+            instances = instances
+                .into_iter()
+                .map(|(_, mut instance)| {
+                    remap_instance_refs(&mut instance, &new_refs);
+                    (instance.referent, instance)
+                })
+                .collect();
+
+            new_refs[&root_ref]
+        } else {
+            if instances
+                .keys()
+                .any(|referent| self.instances.contains_key(referent))
+            {
+                panic!("cannot merge instances whose referents are already in the DOM");
+            }
+
+            root_ref
+        };
+
+        instances.get_mut(&root_ref).unwrap().parent = parent_ref;
+        self.instances.extend(instances);
+        self.instances
+            .get_mut(&parent_ref)
+            .unwrap()
+            .children
+            .push(root_ref);
+
+        root_ref
+    }
+
+    /// Creates a copy of this DOM where every instance has a new referent.
+    /// Ref properties and object `Content` properties pointing to instances in
+    /// the DOM are rewritten to point to their copies, and ones pointing
+    /// anywhere else are left alone.
+    ///
+    /// Unlike copying the instances as-is, the copy can be merged into the
+    /// same DOM as the original without any referents colliding.
+    pub fn clone_with_fresh_refs(&self) -> WeakDom {
+        let new_refs = fresh_refs(&self.instances);
+
+        let instances = self
+            .instances
+            .values()
+            .map(|instance| {
+                let mut copy = Instance {
+                    referent: instance.referent,
+                    children: instance.children.clone(),
+                    parent: instance.parent,
+                    name: instance.name.clone(),
+                    class: instance.class.clone(),
+                    properties: instance.properties.clone(),
+                    unknown_properties: instance.unknown_properties.clone(),
+                };
+                remap_instance_refs(&mut copy, &new_refs);
+
+                (copy.referent, copy)
+            })
+            .collect();
+
+        WeakDom {
+            instances,
+            root_ref: new_refs[&self.root_ref],
+        }
+    }
+}
+
+/// Picks a new referent for every instance in the given map.
+fn fresh_refs(instances: &HashMap<Ref, Instance>) -> HashMap<Ref, Ref> {
+    instances
+        .keys()
+        .map(|&referent| (referent, Ref::new()))
+        .collect()
+}
+
+/// Rewrites every referent stored in the instance, including its own and those
+/// in `Content` properties that point to objects, using the given map.
+/// Referents that aren't in the map are left alone.
+fn remap_instance_refs(instance: &mut Instance, new_refs: &HashMap<Ref, Ref>) {
+    let remap = |referent: Ref| new_refs.get(&referent).copied().unwrap_or(referent);
+
+    instance.referent = remap(instance.referent);
+    instance.parent = remap(instance.parent);
+
+    for child_ref in &mut instance.children {
+        *child_ref = remap(*child_ref);
+    }
+
+    for value in instance.properties.values_mut() {
+        match value {
+            Variant::Ref(target) => *target = remap(*target),
+            Variant::Content(content) => {
+                if let Some(target) = content.as_object() {
+                    *content = Content::from_object(remap(target));
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    use crate::DomViewer;
+    use crate::{DomViewer, RawProperty};
 
     #[test]
     fn transfer() {
@@ -263,17 +1053,856 @@ mod test {
     }
 
     #[test]
-    fn transfer_within() {
-        let subject = InstanceBuilder::new("Folder")
-            .with_name("Root")
-            .with_child(InstanceBuilder::new("SpawnLocation"));
-        let subject_ref = subject.referent;
+    fn transfer_returns_moved_refs() {
+        let grandchild = InstanceBuilder::new("Part");
+        let grandchild_ref = grandchild.referent;
 
-        let source_parent = InstanceBuilder::new("Folder")
-            .with_name("Source")
-            .with_child(subject);
+        let child = InstanceBuilder::new("Model").with_child(grandchild);
+        let child_ref = child.referent;
 
-        let dest_parent = InstanceBuilder::new("Folder").with_name("Dest");
+        let target = InstanceBuilder::new("Folder").with_child(child);
+        let target_ref = target.referent;
+
+        let mut source = WeakDom::new(InstanceBuilder::new("Folder").with_child(target));
+        let mut dest = WeakDom::new(InstanceBuilder::new("DataModel"));
+
+        let dest_root = dest.root_ref();
+        let moved = source.transfer(target_ref, &mut dest, dest_root);
+
+        assert_eq!(moved, vec![target_ref, child_ref, grandchild_ref]);
+
+        for referent in moved {
+            assert!(dest.get_by_ref(referent).is_some());
+            assert!(source.get_by_ref(referent).is_none());
+        }
+    }
+
+    #[test]
+    fn retain_children() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder").with_children(vec![
+            InstanceBuilder::new("Folder").with_name("Keep A"),
+            InstanceBuilder::new("Folder")
+                .with_name("Temp")
+                .with_child(InstanceBuilder::new("Part").with_name("Temp Child")),
+            InstanceBuilder::new("Folder").with_name("Keep B"),
+            InstanceBuilder::new("Script").with_name("Temp"),
+        ]));
+
+        let root_ref = dom.root_ref();
+        let removed = dom.retain_children(root_ref, |instance| instance.name != "Temp");
+
+        let survivors: Vec<&str> = dom
+            .root()
+            .children()
+            .iter()
+            .map(|&child| dom.get_by_ref(child).unwrap().name.as_str())
+            .collect();
+        assert_eq!(survivors, ["Keep A", "Keep B"]);
+
+        // The root and the two survivors are all that should be left.
+        assert_eq!(dom.instances.len(), 3);
+
+        assert_eq!(removed.len(), 2);
+        assert_eq!(removed[0].root().name, "Temp");
+        assert_eq!(removed[0].root().class, "Folder");
+        assert!(removed[0].root().parent().is_none());
+        assert_eq!(removed[0].instances.len(), 2);
+        assert_eq!(removed[1].root().class, "Script");
+        assert_eq!(removed[1].instances.len(), 1);
+    }
+
+    #[test]
+    fn with_capacity() {
+        let dom = WeakDom::with_capacity(
+            InstanceBuilder::new("Folder")
+                .with_name("Root")
+                .with_child(InstanceBuilder::new("StringValue").with_property("Value", "Hi")),
+            100,
+        );
+
+        let root = dom.root();
+        assert_eq!(root.name, "Root");
+        assert_eq!(root.parent(), Ref::none());
+        assert_eq!(root.children().len(), 1);
+
+        let child = dom.get_by_ref(root.children()[0]).unwrap();
+        assert_eq!(child.class, "StringValue");
+        assert_eq!(child.parent(), dom.root_ref());
+        assert_eq!(child.properties.get("Value"), Some(&Variant::from("Hi")));
+    }
+
+    #[test]
+    fn contains_and_is_ancestor_of() {
+        let grandchild = InstanceBuilder::new("Folder");
+        let grandchild_ref = grandchild.referent;
+
+        let child = InstanceBuilder::new("Folder").with_child(grandchild);
+        let child_ref = child.referent;
+
+        let other = InstanceBuilder::new("Folder");
+        let other_ref = other.referent;
+
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(child)
+                .with_child(other),
+        );
+        let root_ref = dom.root_ref();
+        let missing_ref = Ref::new();
+
+        assert!(dom.contains(grandchild_ref));
+        assert!(!dom.contains(missing_ref));
+        assert!(!dom.contains(Ref::none()));
+
+        // Direct
+        assert!(dom.is_ancestor_of(child_ref, grandchild_ref));
+        assert!(dom.is_ancestor_of(root_ref, child_ref));
+
+        // Indirect
+        assert!(dom.is_ancestor_of(root_ref, grandchild_ref));
+
+        // Unrelated, reversed, or identical
+        assert!(!dom.is_ancestor_of(other_ref, grandchild_ref));
+        assert!(!dom.is_ancestor_of(grandchild_ref, child_ref));
+        assert!(!dom.is_ancestor_of(child_ref, child_ref));
+
+        // Missing
+        assert!(!dom.is_ancestor_of(missing_ref, child_ref));
+        assert!(!dom.is_ancestor_of(root_ref, missing_ref));
+    }
+
+    #[test]
+    fn descendants_with_depth() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_name("Root")
+                .with_child(
+                    InstanceBuilder::new("Folder").with_name("A").with_child(
+                        InstanceBuilder::new("Folder")
+                            .with_name("A1")
+                            .with_child(InstanceBuilder::new("Folder").with_name("A1a")),
+                    ),
+                )
+                .with_child(InstanceBuilder::new("Folder").with_name("B")),
+        );
+
+        let descendants: Vec<(usize, &str)> = dom
+            .descendants_with_depth(dom.root_ref())
+            .map(|(depth, instance)| (depth, instance.name.as_str()))
+            .collect();
+
+        assert_eq!(descendants, vec![(1, "A"), (2, "A1"), (3, "A1a"), (1, "B")]);
+    }
+
+    #[test]
+    fn for_each_descendant_mut() {
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_property("Source", "root")
+                .with_child(
+                    InstanceBuilder::new("Script")
+                        .with_property("Source", "print('a')")
+                        .with_child(
+                            InstanceBuilder::new("ModuleScript")
+                                .with_property("Source", "return 1"),
+                        ),
+                )
+                .with_child(InstanceBuilder::new("Script").with_property("Source", "print('b')")),
+        );
+
+        let mut visited = Vec::new();
+        dom.for_each_descendant_mut(dom.root_ref(), |instance| {
+            visited.push(instance.class.clone());
+            instance.properties.remove("Source");
+        });
+
+        assert_eq!(visited, vec!["Script", "ModuleScript", "Script"]);
+        assert!(dom.root().properties.contains_key("Source"));
+
+        let descendants: Vec<&Instance> = dom
+            .descendants_with_depth(dom.root_ref())
+            .map(|(_, instance)| instance)
+            .collect();
+
+        assert_eq!(descendants.len(), 3);
+        for instance in descendants {
+            assert!(instance.properties.is_empty());
+        }
+    }
+
+    #[test]
+    fn insert_many() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let root_ref = dom.root_ref();
+
+        let grandchild = InstanceBuilder::new("Part").with_name("Grandchild");
+        let child = InstanceBuilder::new("Model").with_name("Child");
+        let sibling = InstanceBuilder::new("Folder").with_name("Sibling");
+        let child_ref = child.referent;
+
+        dom.insert_many(vec![
+            (grandchild, child_ref),
+            (child, root_ref),
+            (sibling, root_ref),
+        ])
+        .unwrap();
+
+        let children = dom.root().children();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0], child_ref);
+        assert_eq!(dom.get_by_ref(children[1]).unwrap().name, "Sibling");
+
+        let child = dom.get_by_ref(child_ref).unwrap();
+        assert_eq!(child.parent(), root_ref);
+        assert_eq!(child.children().len(), 1);
+
+        let grandchild = dom.get_by_ref(child.children()[0]).unwrap();
+        assert_eq!(grandchild.name, "Grandchild");
+        assert_eq!(grandchild.parent(), child_ref);
+    }
+
+    #[test]
+    fn insert_many_missing_parent() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let root_ref = dom.root_ref();
+
+        let orphan = InstanceBuilder::new("Folder");
+        let orphan_ref = orphan.referent;
+
+        let result = dom.insert_many(vec![
+            (InstanceBuilder::new("Folder"), root_ref),
+            (orphan, Ref::new()),
+        ]);
+
+        assert_eq!(result, Err(vec![orphan_ref]));
+        assert!(dom.root().children().is_empty());
+    }
+
+    #[test]
+    fn swap_properties() {
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("StringValue")
+                .with_name("Greeting")
+                .with_property("Value", "Hello"),
+        );
+        let root_ref = dom.root_ref();
+
+        let mut new_properties = HashMap::new();
+        new_properties.insert("Value".to_owned(), Variant::from("Goodbye"));
+
+        let old_properties = dom.swap_properties(root_ref, new_properties).unwrap();
+        assert_eq!(old_properties.len(), 1);
+        assert_eq!(old_properties.get("Value"), Some(&Variant::from("Hello")));
+
+        let root = dom.root();
+        assert_eq!(root.name, "Greeting");
+        assert_eq!(root.class, "StringValue");
+        assert_eq!(root.properties.len(), 1);
+        assert_eq!(
+            root.properties.get("Value"),
+            Some(&Variant::from("Goodbye"))
+        );
+
+        assert!(dom.swap_properties(Ref::new(), HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn rename_and_set_class() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let child_ref = dom.insert(
+            dom.root_ref(),
+            InstanceBuilder::new("Folder")
+                .with_name("Old")
+                .with_property("Archivable", true),
+        );
+
+        assert!(dom.rename(child_ref, "New"));
+        assert!(dom.set_class(child_ref, "Model"));
+
+        let child = dom.get_by_ref(child_ref).unwrap();
+        assert_eq!(child.name, "New");
+        assert_eq!(child.class, "Model");
+        assert_eq!(
+            child.properties.get("Archivable"),
+            Some(&Variant::Bool(true))
+        );
+
+        let root = dom.root();
+        assert_eq!(root.name, "Folder");
+        assert_eq!(root.class, "Folder");
+
+        assert!(!dom.rename(Ref::new(), "Missing"));
+        assert!(!dom.set_class(Ref::new(), "Missing"));
+    }
+
+    #[test]
+    fn remove_and_has_property() {
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Part")
+                .with_property("Anchored", true)
+                .with_property("CanCollide", false),
+        );
+        let root_ref = dom.root_ref();
+
+        assert!(dom.has_property(root_ref, "Anchored"));
+        assert_eq!(
+            dom.remove_property(root_ref, "Anchored"),
+            Some(Variant::Bool(true))
+        );
+        assert!(!dom.has_property(root_ref, "Anchored"));
+        assert!(dom.has_property(root_ref, "CanCollide"));
+
+        assert_eq!(dom.remove_property(root_ref, "Anchored"), None);
+        assert_eq!(dom.remove_property(root_ref, "Transparency"), None);
+
+        assert_eq!(dom.remove_property(Ref::new(), "CanCollide"), None);
+        assert!(!dom.has_property(Ref::new(), "CanCollide"));
+    }
+
+    #[test]
+    fn merge_remap_refs() {
+        let model = InstanceBuilder::new("Model").with_name("Car");
+        let model_ref = model.referent;
+
+        let seat = InstanceBuilder::new("Seat");
+        let seat_ref = seat.referent;
+
+        let other = WeakDom::new(
+            model
+                .with_property("PrimaryPart", seat_ref)
+                .with_child(seat.with_property("Owner", model_ref)),
+        );
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Workspace"));
+        let workspace_ref = dom.root_ref();
+        let new_model_ref = dom.merge(other, workspace_ref, true);
+
+        assert_ne!(new_model_ref, model_ref);
+        assert!(!dom.contains(model_ref));
+        assert!(!dom.contains(seat_ref));
+        assert_eq!(dom.root().children(), &[new_model_ref]);
+
+        let new_model = dom.get_by_ref(new_model_ref).unwrap();
+        assert_eq!(new_model.name, "Car");
+        assert_eq!(new_model.parent(), workspace_ref);
+
+        let new_seat_ref = new_model.children()[0];
+        assert_ne!(new_seat_ref, seat_ref);
+        assert_eq!(
+            new_model.properties.get("PrimaryPart"),
+            Some(&Variant::Ref(new_seat_ref))
+        );
+
+        let new_seat = dom.get_by_ref(new_seat_ref).unwrap();
+        assert_eq!(new_seat.referent(), new_seat_ref);
+        assert_eq!(new_seat.parent(), new_model_ref);
+        assert_eq!(
+            new_seat.properties.get("Owner"),
+            Some(&Variant::Ref(new_model_ref))
+        );
+    }
+
+    #[test]
+    fn merge_remap_content_refs() {
+        let image = InstanceBuilder::new("EditableImage");
+        let image_ref = image.referent;
+
+        let other = WeakDom::new(
+            InstanceBuilder::new("ImageLabel")
+                .with_property("ImageContent", Content::from_object(image_ref))
+                .with_child(image),
+        );
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Workspace"));
+        let workspace_ref = dom.root_ref();
+        let label_ref = dom.merge(other, workspace_ref, true);
+
+        let label = dom.get_by_ref(label_ref).unwrap();
+        let new_image_ref = label.children()[0];
+        assert_ne!(new_image_ref, image_ref);
+        assert_eq!(
+            label.properties.get("ImageContent"),
+            Some(&Variant::Content(Content::from_object(new_image_ref)))
+        );
+    }
+
+    #[test]
+    fn merge_keep_refs() {
+        let other =
+            WeakDom::new(InstanceBuilder::new("Folder").with_child(InstanceBuilder::new("Part")));
+        let other_root_ref = other.root_ref();
+        let part_ref = other.root().children()[0];
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Workspace"));
+        let workspace_ref = dom.root_ref();
+
+        assert_eq!(dom.merge(other, workspace_ref, false), other_root_ref);
+        assert_eq!(dom.root().children(), &[other_root_ref]);
+        assert_eq!(dom.get_by_ref(part_ref).unwrap().parent(), other_root_ref);
+    }
+
+    #[test]
+    #[should_panic(expected = "already in the DOM")]
+    fn merge_keep_refs_collision() {
+        let part = InstanceBuilder::new("Part");
+        let mut duplicate = InstanceBuilder::new("Part");
+        duplicate.referent = part.referent;
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Workspace").with_child(part));
+        let workspace_ref = dom.root_ref();
+
+        let other = WeakDom::new(InstanceBuilder::new("Folder").with_child(duplicate));
+        dom.merge(other, workspace_ref, false);
+    }
+
+    #[test]
+    fn dump_tree() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Model")
+                .with_name("Car")
+                .with_child(
+                    InstanceBuilder::new("Part")
+                        .with_name("Body")
+                        .with_property("Anchored", true)
+                        .with_child(InstanceBuilder::new("Decal")),
+                )
+                .with_child(InstanceBuilder::new("Seat")),
+        );
+
+        assert_eq!(
+            dom.dump_tree(dom.root_ref(), false),
+            "Model \"Car\"\n  Part \"Body\"\n    Decal \"Decal\"\n  Seat \"Seat\"\n"
+        );
+
+        let body_ref = dom.root().children()[0];
+        assert_eq!(
+            dom.dump_tree(body_ref, true),
+            "Part \"Body\"\n  .Anchored = Bool(true)\n  Decal \"Decal\"\n"
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_for_each() {
+        use std::sync::Mutex;
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let mut expected = vec![dom.root_ref()];
+
+        for _ in 0..10 {
+            let child_ref = dom.insert(dom.root_ref(), InstanceBuilder::new("Folder"));
+            expected.push(child_ref);
+            expected.push(dom.insert(child_ref, InstanceBuilder::new("Part")));
+        }
+
+        let visited = Mutex::new(Vec::new());
+        dom.par_for_each(|instance| visited.lock().unwrap().push(instance.referent()));
+
+        let mut visited = visited.into_inner().unwrap();
+        visited.sort_unstable_by_key(|referent| referent.to_string());
+        expected.sort_unstable_by_key(|referent| referent.to_string());
+
+        assert_eq!(visited, expected);
+    }
+
+    #[test]
+    fn dangling_refs() {
+        let target = InstanceBuilder::new("Part");
+        let target_ref = target.referent;
+        let missing_ref = Ref::new();
+
+        let valid = InstanceBuilder::new("ObjectValue").with_property("Value", target_ref);
+        let dangling = InstanceBuilder::new("ObjectValue").with_property("Value", missing_ref);
+        let dangling_ref = dangling.referent;
+        let null = InstanceBuilder::new("ObjectValue").with_property("Value", Ref::none());
+        let dangling_content = InstanceBuilder::new("ImageLabel")
+            .with_property("ImageContent", Content::from_object(missing_ref));
+        let dangling_content_ref = dangling_content.referent;
+
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(target)
+                .with_child(valid)
+                .with_child(dangling)
+                .with_child(null)
+                .with_child(dangling_content),
+        );
+
+        assert_eq!(
+            dom.dangling_refs(),
+            vec![
+                (dangling_ref, "Value".to_owned(), missing_ref),
+                (dangling_content_ref, "ImageContent".to_owned(), missing_ref),
+            ]
+        );
+    }
+
+    #[test]
+    fn iter_topological() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_name("Root")
+                .with_child(
+                    InstanceBuilder::new("Folder")
+                        .with_name("A")
+                        .with_child(
+                            InstanceBuilder::new("Folder")
+                                .with_name("A1")
+                                .with_child(InstanceBuilder::new("Part").with_name("A1a")),
+                        )
+                        .with_child(InstanceBuilder::new("Part").with_name("A2")),
+                )
+                .with_child(
+                    InstanceBuilder::new("Folder")
+                        .with_name("B")
+                        .with_child(InstanceBuilder::new("Part").with_name("B1")),
+                ),
+        );
+
+        let order: Vec<&Instance> = dom.iter_topological(dom.root_ref()).collect();
+        assert_eq!(order.len(), 7);
+        assert_eq!(order[0].referent(), dom.root_ref());
+
+        let mut seen = HashSet::new();
+        for instance in &order {
+            if instance.referent() != dom.root_ref() {
+                assert!(
+                    seen.contains(&instance.parent()),
+                    "{} appeared before its parent",
+                    instance.name
+                );
+            }
+
+            seen.insert(instance.referent());
+        }
+
+        let subtree_ref = dom.root().children()[0];
+        let names: Vec<&str> = dom
+            .iter_topological(subtree_ref)
+            .map(|instance| instance.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["A", "A1", "A2", "A1a"]);
+    }
+
+    #[test]
+    fn structural_eq() {
+        fn build(name: &str) -> WeakDom {
+            let mut dom = WeakDom::new(InstanceBuilder::new("Model").with_name("Car"));
+            let body_ref = dom.insert(
+                dom.root_ref(),
+                InstanceBuilder::new("Part")
+                    .with_name("Body")
+                    .with_property("Anchored", true),
+            );
+            dom.insert(dom.root_ref(), InstanceBuilder::new("Part").with_name(name));
+            dom.root_mut()
+                .set_property("PrimaryPart", Variant::Ref(body_ref));
+            dom.root_mut()
+                .set_property("Thumbnail", Content::from_object(body_ref));
+
+            dom
+        }
+
+        let dom = build("Wheel");
+        let same = build("Wheel");
+        assert!(dom.structural_eq(&same));
+        assert!(same.structural_eq(&dom));
+
+        let renamed = build("Tire");
+        assert!(!dom.structural_eq(&renamed));
+
+        let mut repointed = build("Wheel");
+        let wheel_ref = repointed.root().children()[1];
+        repointed
+            .root_mut()
+            .set_property("PrimaryPart", Variant::Ref(wheel_ref));
+        assert!(!dom.structural_eq(&repointed));
+
+        let mut repointed_content = build("Wheel");
+        let wheel_ref = repointed_content.root().children()[1];
+        repointed_content
+            .root_mut()
+            .set_property("Thumbnail", Content::from_object(wheel_ref));
+        assert!(!dom.structural_eq(&repointed_content));
+
+        let mut extra = build("Wheel");
+        extra.insert(extra.root_ref(), InstanceBuilder::new("Folder"));
+        assert!(!dom.structural_eq(&extra));
+
+        let mut raw = build("Wheel");
+        raw.root_mut().unknown_properties.insert(
+            "Fancy".to_owned(),
+            RawProperty::Xml {
+                type_name: "FancyType".to_owned(),
+                contents: "1".to_owned(),
+            },
+        );
+        assert!(!dom.structural_eq(&raw));
+
+        let mut raw_changed = build("Wheel");
+        raw_changed.root_mut().unknown_properties.insert(
+            "Fancy".to_owned(),
+            RawProperty::Xml {
+                type_name: "FancyType".to_owned(),
+                contents: "2".to_owned(),
+            },
+        );
+        assert!(!raw.structural_eq(&raw_changed));
+        assert!(raw.structural_eq(&raw.clone_with_fresh_refs()));
+
+        let nan =
+            WeakDom::new(InstanceBuilder::new("NumberValue").with_property("Value", std::f64::NAN));
+        assert!(nan.structural_eq(&nan));
+    }
+
+    #[test]
+    fn canonical_accessor_names() {
+        // These names replaced get_instance, get_instance_mut, and
+        // get_children_ids. Downstream code and docs rely on them.
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let child_ref = dom.insert(dom.root_ref(), InstanceBuilder::new("StringValue"));
+
+        assert_eq!(dom.root().children(), &[child_ref]);
+        assert_eq!(
+            dom.get_by_ref(dom.root_ref()).unwrap().children(),
+            &[child_ref]
+        );
+
+        dom.get_by_ref_mut(child_ref).unwrap().name = "Renamed".to_owned();
+        assert_eq!(dom.get_by_ref(child_ref).unwrap().name, "Renamed");
+        assert_eq!(dom.get_by_ref(child_ref).unwrap().parent(), dom.root_ref());
+    }
+
+    #[test]
+    fn property_names() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(InstanceBuilder::new("Part").with_property("Anchored", true))
+                .with_child(
+                    InstanceBuilder::new("Part")
+                        .with_property("Anchored", false)
+                        .with_property("Transparency", 0.5f32),
+                )
+                .with_child(InstanceBuilder::new("StringValue").with_property("Value", "Hi")),
+        );
+
+        let all: Vec<_> = dom.all_property_names().into_iter().collect();
+        assert_eq!(all, ["Anchored", "Transparency", "Value"]);
+
+        let parts: Vec<_> = dom.property_names_for_class("Part").into_iter().collect();
+        assert_eq!(parts, ["Anchored", "Transparency"]);
+
+        assert!(dom.property_names_for_class("Folder").is_empty());
+        assert!(dom.property_names_for_class("Model").is_empty());
+    }
+
+    #[test]
+    fn validate_names() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let unnamed_ref = dom.insert(dom.root_ref(), InstanceBuilder::new("Part").with_name(""));
+        dom.insert(dom.root_ref(), InstanceBuilder::new("Model"));
+
+        assert_eq!(dom.validate_names(), vec![unnamed_ref]);
+
+        dom.rename(unnamed_ref, "Part");
+        assert!(dom.validate_names().is_empty());
+    }
+
+    #[test]
+    fn clone_with_fresh_refs() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("Model"));
+        let part_ref = dom.insert(dom.root_ref(), InstanceBuilder::new("Part"));
+        let value_ref = dom.insert(
+            dom.root_ref(),
+            InstanceBuilder::new("ObjectValue")
+                .with_property("Value", part_ref)
+                .with_property("Outside", Ref::new()),
+        );
+
+        let copy = dom.clone_with_fresh_refs();
+
+        assert!(copy.structural_eq(&dom));
+        for referent in dom.instances.keys() {
+            assert!(!copy.contains(*referent));
+        }
+
+        let copy_root = copy.root();
+        assert_eq!(copy_root.parent(), Ref::none());
+
+        let copy_part_ref = copy_root.children()[0];
+        let copy_value = copy.get_by_ref(copy_root.children()[1]).unwrap();
+        assert_eq!(copy.get_by_ref(copy_part_ref).unwrap().class, "Part");
+        assert_eq!(copy_value.parent(), copy.root_ref());
+        assert_eq!(
+            copy_value.properties.get("Value"),
+            Some(&Variant::Ref(copy_part_ref))
+        );
+        assert_eq!(
+            copy_value.properties.get("Outside"),
+            dom.get_by_ref(value_ref).unwrap().properties.get("Outside")
+        );
+    }
+
+    #[test]
+    fn subtree_hash() {
+        fn model() -> InstanceBuilder {
+            let handle = InstanceBuilder::new("Part").with_name("Handle");
+            let handle_ref = handle.referent();
+
+            InstanceBuilder::new("Model")
+                .with_property("PrimaryPart", handle_ref)
+                .with_child(handle)
+                .with_child(InstanceBuilder::new("Part").with_name("Blade"))
+        }
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let first = dom.insert(dom.root_ref(), model());
+        let second = dom.insert(dom.root_ref(), model());
+
+        assert_eq!(dom.subtree_hash(first), dom.subtree_hash(second));
+
+        // Pointing PrimaryPart at a different part of the subtree changes the
+        // hash, even though it's still a non-null ref.
+        let blade = dom.get_by_ref(second).unwrap().children()[1];
+        dom.get_by_ref_mut(second)
+            .unwrap()
+            .set_property("PrimaryPart", blade);
+        assert_ne!(dom.subtree_hash(first), dom.subtree_hash(second));
+
+        let handle = dom.get_by_ref(second).unwrap().children()[0];
+        dom.get_by_ref_mut(second)
+            .unwrap()
+            .set_property("PrimaryPart", handle);
+        assert_eq!(dom.subtree_hash(first), dom.subtree_hash(second));
+
+        // Changes to descendants change the hash of their ancestors.
+        dom.get_by_ref_mut(handle).unwrap().name = "Grip".to_owned();
+        assert_ne!(dom.subtree_hash(first), dom.subtree_hash(second));
+
+        let first_hash = dom.get_by_ref(first).unwrap().content_hash();
+        let second_hash = dom.get_by_ref(second).unwrap().content_hash();
+        assert_eq!(first_hash, second_hash);
+    }
+
+    #[test]
+    fn subtree_hash_content_objects() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        let mut labels = Vec::new();
+
+        for _ in 0..2 {
+            let label_ref = dom.insert(dom.root_ref(), InstanceBuilder::new("ImageLabel"));
+            let image_ref = dom.insert(label_ref, InstanceBuilder::new("EditableImage"));
+            dom.insert(label_ref, InstanceBuilder::new("EditableImage"));
+            dom.get_by_ref_mut(label_ref)
+                .unwrap()
+                .set_property("ImageContent", Content::from_object(image_ref));
+            labels.push(label_ref);
+        }
+
+        assert_eq!(dom.subtree_hash(labels[0]), dom.subtree_hash(labels[1]));
+
+        // Pointing at the other image inside the subtree changes the hash.
+        let other_image = dom.get_by_ref(labels[1]).unwrap().children()[1];
+        dom.get_by_ref_mut(labels[1])
+            .unwrap()
+            .set_property("ImageContent", Content::from_object(other_image));
+        assert_ne!(dom.subtree_hash(labels[0]), dom.subtree_hash(labels[1]));
+
+        // An empty URL doesn't hash like an object.
+        dom.get_by_ref_mut(labels[1])
+            .unwrap()
+            .set_property("ImageContent", Content::new());
+        assert_ne!(dom.subtree_hash(labels[0]), dom.subtree_hash(labels[1]));
+    }
+
+    #[test]
+    fn replace_subtree() {
+        let target = InstanceBuilder::new("Folder")
+            .with_name("Target")
+            .with_child(InstanceBuilder::new("Part").with_name("Old"));
+        let target_ref = target.referent;
+
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(InstanceBuilder::new("Folder").with_name("Before"))
+                .with_child(target)
+                .with_child(InstanceBuilder::new("Folder").with_name("After")),
+        );
+        let root_ref = dom.root_ref();
+        let old_child_ref = dom.get_by_ref(target_ref).unwrap().children()[0];
+
+        let replacement = WeakDom::new(
+            InstanceBuilder::new("Model")
+                .with_name("Replacement")
+                .with_child(InstanceBuilder::new("Part").with_name("New")),
+        );
+
+        dom.replace_subtree(target_ref, replacement);
+
+        assert_eq!(dom.root().children()[1], target_ref);
+        assert!(dom.get_by_ref(old_child_ref).is_none());
+
+        let replaced = dom.get_by_ref(target_ref).unwrap();
+        assert_eq!(replaced.name, "Replacement");
+        assert_eq!(replaced.class, "Model");
+        assert_eq!(replaced.parent(), root_ref);
+        assert_eq!(replaced.children().len(), 1);
+
+        let new_child = dom.get_by_ref(replaced.children()[0]).unwrap();
+        assert_eq!(new_child.name, "New");
+        assert_eq!(new_child.parent(), target_ref);
+    }
+
+    #[test]
+    fn replace_subtree_same_refs() {
+        let child = InstanceBuilder::new("Part").with_name("Old");
+        let mut edited_child = InstanceBuilder::new("Part").with_name("Edited");
+        edited_child.referent = child.referent;
+        let child_ref = child.referent;
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder").with_child(child));
+        let root_ref = dom.root_ref();
+
+        dom.replace_subtree(
+            root_ref,
+            WeakDom::new(InstanceBuilder::new("Folder").with_child(edited_child)),
+        );
+
+        assert_eq!(dom.root().children(), &[child_ref]);
+        assert_eq!(dom.get_by_ref(child_ref).unwrap().name, "Edited");
+    }
+
+    #[test]
+    #[should_panic(expected = "already in the DOM")]
+    fn replace_subtree_collision() {
+        let target = InstanceBuilder::new("Folder").with_name("Target");
+        let target_ref = target.referent;
+        let sibling = InstanceBuilder::new("Part").with_name("Sibling");
+        let mut duplicate = InstanceBuilder::new("Part");
+        duplicate.referent = sibling.referent;
+
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(target)
+                .with_child(sibling),
+        );
+
+        let replacement = WeakDom::new(InstanceBuilder::new("Model").with_child(duplicate));
+        dom.replace_subtree(target_ref, replacement);
+    }
+
+    #[test]
+    fn transfer_within() {
+        let subject = InstanceBuilder::new("Folder")
+            .with_name("Root")
+            .with_child(InstanceBuilder::new("SpawnLocation"));
+        let subject_ref = subject.referent;
+
+        let source_parent = InstanceBuilder::new("Folder")
+            .with_name("Source")
+            .with_child(subject);
+
+        let dest_parent = InstanceBuilder::new("Folder").with_name("Dest");
         let dest_parent_ref = dest_parent.referent;
 
         let mut dom = WeakDom::new(
@@ -292,4 +1921,20 @@ mod test {
         // This snapshot should have Root and SpawnLocation contained in Dest.
         insta::assert_yaml_snapshot!(viewer.view_children(&dom));
     }
+
+    #[test]
+    #[should_panic(expected = "would create a cycle")]
+    fn transfer_within_cycle() {
+        let child = InstanceBuilder::new("Folder").with_name("Child");
+        let child_ref = child.referent;
+
+        let parent = InstanceBuilder::new("Folder")
+            .with_name("Parent")
+            .with_child(child);
+        let parent_ref = parent.referent;
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder").with_child(parent));
+
+        dom.transfer_within(parent_ref, child_ref);
+    }
 }
@@ -0,0 +1,74 @@
+use std::hash::{Hash, Hasher};
+
+use rbx_types::{Ref, Variant};
+
+use crate::Instance;
+
+/// Feeds the class, name, and properties of an instance into `state`.
+///
+/// Referents can't be hashed directly because they're different every time a
+/// file is loaded. Instead, `ref_position` maps the target of a `Ref` property,
+/// or of a `Content` property that points to an instance, to a stable
+/// position, or returns `None` if the target should only be hashed as "some
+/// instance".
+pub(crate) fn hash_instance<H, F>(instance: &Instance, state: &mut H, ref_position: F)
+where
+    H: Hasher,
+    F: Fn(Ref) -> Option<usize>,
+{
+    instance.class.hash(state);
+    instance.name.hash(state);
+
+    // Property maps are unordered, so properties need to be sorted by name
+    // for the hash to be deterministic.
+    let mut properties: Vec<_> = instance.properties.iter().collect();
+    properties.sort_unstable_by_key(|(name, _)| name.as_str());
+
+    properties.len().hash(state);
+    for (name, value) in properties {
+        name.hash(state);
+        hash_variant(value, state, &ref_position);
+    }
+
+    let mut unknown_properties: Vec<_> = instance.unknown_properties.iter().collect();
+    unknown_properties.sort_unstable_by_key(|(name, _)| name.as_str());
+    unknown_properties.hash(state);
+}
+
+fn hash_variant<H, F>(value: &Variant, state: &mut H, ref_position: &F)
+where
+    H: Hasher,
+    F: Fn(Ref) -> Option<usize>,
+{
+    match value {
+        Variant::Ref(referent) => {
+            "Ref".hash(state);
+            hash_ref(*referent, state, ref_position);
+        }
+
+        Variant::Content(content) => match content.as_object() {
+            Some(referent) => {
+                "ContentObject".hash(state);
+                hash_ref(referent, state, ref_position);
+            }
+            None => value.bitwise_hash(state),
+        },
+
+        _ => value.bitwise_hash(state),
+    }
+}
+
+fn hash_ref<H, F>(referent: Ref, state: &mut H, ref_position: &F)
+where
+    H: Hasher,
+    F: Fn(Ref) -> Option<usize>,
+{
+    if referent.is_none() {
+        0u8.hash(state);
+    } else if let Some(position) = ref_position(referent) {
+        1u8.hash(state);
+        position.hash(state);
+    } else {
+        2u8.hash(state);
+    }
+}
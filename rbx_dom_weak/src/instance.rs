@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{hash_map::DefaultHasher, HashMap};
+use std::hash::Hasher;
 
 use rbx_types::{Ref, Variant};
 
@@ -56,6 +57,30 @@ impl InstanceBuilder {
         }
     }
 
+    /// Create a new `InstanceBuilder` for a value object like `StringValue` or
+    /// `CFrameValue`, with its `Value` property set to the given value.
+    pub fn value_instance<S: Into<String>, V: Into<Variant>>(class: S, value: V) -> Self {
+        InstanceBuilder::new(class).with_property("Value", value)
+    }
+
+    /// Create a new `InstanceBuilder` for a `StringValue` holding the given
+    /// string.
+    pub fn string_value<S: Into<String>>(value: S) -> Self {
+        InstanceBuilder::value_instance("StringValue", value.into())
+    }
+
+    /// Create a new `InstanceBuilder` for a `BoolValue` holding the given
+    /// bool.
+    pub fn bool_value(value: bool) -> Self {
+        InstanceBuilder::value_instance("BoolValue", value)
+    }
+
+    /// Create a new `InstanceBuilder` for a `NumberValue` holding the given
+    /// number.
+    pub fn number_value(value: f64) -> Self {
+        InstanceBuilder::value_instance("NumberValue", value)
+    }
+
     /// Return the referent of the instance that the `InstanceBuilder` refers to.
     pub fn referent(&self) -> Ref {
         self.referent
@@ -162,6 +187,17 @@ pub struct Instance {
 
     /// Any properties stored on the object that are not `Name` or `ClassName`.
     pub properties: HashMap<String, Variant>,
+
+    /// Properties with types that couldn't be decoded, kept in their
+    /// serialized form so that they can be written back out unchanged.
+    ///
+    /// This is only filled in when a decoder is asked to preserve unknown
+    /// types, like with rbx_xml's `DecodeOptions::preserve_unknown_types`, or
+    /// to leave values undecoded until they're needed, like with rbx_xml's
+    /// `DecodeOptions::lazy_properties`.
+    /// Encoders only write back raw properties in their own format, so these
+    /// are dropped when, for example, an XML model is saved with rbx_binary.
+    pub unknown_properties: HashMap<String, RawProperty>,
 }
 
 impl Instance {
@@ -186,4 +222,222 @@ impl Instance {
     pub fn parent(&self) -> Ref {
         self.parent
     }
+
+    /// Returns the value of the property with the given name, if it is set.
+    #[inline]
+    pub fn get_property(&self, name: &str) -> Option<&Variant> {
+        self.properties.get(name)
+    }
+
+    /// Sets the property with the given name to the given value, returning
+    /// the property's previous value if it had one.
+    #[inline]
+    pub fn set_property<K: Into<String>, V: Into<Variant>>(
+        &mut self,
+        name: K,
+        value: V,
+    ) -> Option<Variant> {
+        self.properties.insert(name.into(), value.into())
+    }
+
+    /// Returns a hash of this instance's class, name, and properties, which
+    /// can be compared against an earlier hash to tell whether the instance
+    /// has changed. Children are not included.
+    ///
+    /// Values are hashed with [`Variant::bitwise_hash`], so floats are hashed
+    /// by their exact bits. `Ref` properties, and `Content` properties that
+    /// point to an instance, are only hashed by whether they're null, since
+    /// referents are different every time a file is loaded.
+    ///
+    /// The hash is the same between runs of a program, but may change between
+    /// versions of rbx_dom_weak or Rust, so it shouldn't be persisted.
+    pub fn content_hash(&self) -> u64 {
+        let mut state = DefaultHasher::new();
+        crate::hash::hash_instance(self, &mut state, |_| None);
+        state.finish()
+    }
+}
+
+/// The serialized form of a property that wasn't decoded, either because its
+/// type is unknown, like a type that Roblox added after the decoder was
+/// written, or because decoding it was put off until later.
+///
+/// New file formats may be added to `RawProperty` in minor releases. As such,
+/// it is marked `#[non_exhaustive]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RawProperty {
+    /// A property from an XML model or place.
+    Xml {
+        /// The name of the element that the property was stored in, which is
+        /// the name of its type, like `UniqueId`.
+        type_name: String,
+
+        /// The XML contained inside of the property's element.
+        contents: String,
+    },
+
+    /// A property from an XML model or place with a known type that was left
+    /// undecoded so that it could be decoded later, like with rbx_xml's
+    /// `DecodeOptions::lazy_properties`.
+    XmlDeferred {
+        /// The name of the element that the property was stored in, which is
+        /// the name of its type, like `Vector3`.
+        type_name: String,
+
+        /// The XML contained inside of the property's element.
+        contents: String,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::WeakDom;
+
+    #[test]
+    fn with_property_inline() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("BoolValue")
+                .with_property("Value", true)
+                .with_properties(vec![("Weight", 0.5f32)]),
+        );
+
+        let root = dom.root();
+        assert_eq!(root.properties.get("Value"), Some(&Variant::Bool(true)));
+        assert_eq!(root.properties.get("Weight"), Some(&Variant::Float32(0.5)));
+    }
+
+    #[test]
+    fn value_instances() {
+        let dom = WeakDom::new(
+            InstanceBuilder::string_value("Hello")
+                .with_name("Greeting")
+                .with_child(InstanceBuilder::bool_value(true))
+                .with_child(InstanceBuilder::number_value(2.5))
+                .with_child(InstanceBuilder::value_instance("IntValue", 7i64)),
+        );
+
+        let root = dom.root();
+        assert_eq!(root.class, "StringValue");
+        assert_eq!(root.name, "Greeting");
+        assert_eq!(
+            root.properties.get("Value"),
+            Some(&Variant::String("Hello".to_owned()))
+        );
+
+        let children: Vec<_> = root
+            .children()
+            .iter()
+            .map(|&referent| dom.get_by_ref(referent).unwrap())
+            .collect();
+
+        assert_eq!(children[0].class, "BoolValue");
+        assert_eq!(
+            children[0].properties.get("Value"),
+            Some(&Variant::Bool(true))
+        );
+        assert_eq!(children[1].class, "NumberValue");
+        assert_eq!(
+            children[1].properties.get("Value"),
+            Some(&Variant::Float64(2.5))
+        );
+        assert_eq!(children[2].class, "IntValue");
+        assert_eq!(
+            children[2].properties.get("Value"),
+            Some(&Variant::Int64(7))
+        );
+    }
+
+    #[test]
+    fn get_and_set_property() {
+        use rbx_types::Vector3;
+
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Vector3Value")
+                .with_property("Value", Vector3::new(1.0, 2.0, 3.0)),
+        );
+
+        let root = dom.root_mut();
+        assert_eq!(
+            root.get_property("Value"),
+            Some(&Variant::Vector3(Vector3::new(1.0, 2.0, 3.0)))
+        );
+        assert_eq!(root.get_property("Missing"), None);
+
+        let previous = root.set_property("Value", Vector3::new(4.0, 5.0, 6.0));
+        assert_eq!(
+            previous,
+            Some(Variant::Vector3(Vector3::new(1.0, 2.0, 3.0)))
+        );
+        assert_eq!(
+            root.get_property("Value"),
+            Some(&Variant::Vector3(Vector3::new(4.0, 5.0, 6.0)))
+        );
+    }
+
+    #[test]
+    fn content_hash() {
+        use rbx_types::{Content, Ref, Vector3};
+
+        let mut dom = WeakDom::new(
+            InstanceBuilder::new("Part")
+                .with_property("Size", Vector3::new(4.0, 1.0, 2.0))
+                .with_property("Anchored", true)
+                .with_property("Transparency", 0.5f32),
+        );
+        let root_ref = dom.root_ref();
+
+        let original = dom.root().content_hash();
+        assert_eq!(dom.root().content_hash(), original);
+
+        // The same contents loaded into a new instance hash identically,
+        // regardless of the referent or property insertion order.
+        let copy = WeakDom::new(
+            InstanceBuilder::new("Part")
+                .with_property("Transparency", 0.5f32)
+                .with_property("Anchored", true)
+                .with_property("Size", Vector3::new(4.0, 1.0, 2.0)),
+        );
+        assert_eq!(copy.root().content_hash(), original);
+
+        dom.root_mut()
+            .set_property("Size", Vector3::new(4.0, 1.0, 3.0));
+        assert_ne!(dom.root().content_hash(), original);
+
+        dom.root_mut()
+            .set_property("Size", Vector3::new(4.0, 1.0, 2.0));
+        assert_eq!(dom.root().content_hash(), original);
+
+        dom.root_mut().set_property("Transparency", -0.0f32);
+        let negative_zero = dom.root().content_hash();
+        dom.root_mut().set_property("Transparency", 0.0f32);
+        assert_ne!(dom.root().content_hash(), negative_zero);
+
+        dom.root_mut().name = "Renamed".to_owned();
+        assert_ne!(dom.root().content_hash(), negative_zero);
+
+        // Refs only hash by whether they point anywhere.
+        dom.root_mut().set_property("PrimaryPart", Ref::none());
+        let null_ref = dom.root().content_hash();
+        dom.root_mut().set_property("PrimaryPart", root_ref);
+        let some_ref = dom.root().content_hash();
+        dom.root_mut().set_property("PrimaryPart", Ref::new());
+        assert_ne!(null_ref, some_ref);
+        assert_eq!(dom.root().content_hash(), some_ref);
+
+        // Content that points to an instance is hashed like a Ref, which is
+        // different from an empty URL.
+        dom.root_mut()
+            .set_property("TextureContent", Content::new());
+        let empty_content = dom.root().content_hash();
+        dom.root_mut()
+            .set_property("TextureContent", Content::from_object(root_ref));
+        let object_content = dom.root().content_hash();
+        dom.root_mut()
+            .set_property("TextureContent", Content::from_object(Ref::new()));
+        assert_ne!(empty_content, object_content);
+        assert_eq!(dom.root().content_hash(), object_content);
+    }
 }
@@ -42,13 +42,16 @@
 #![deny(missing_docs)]
 
 mod dom;
+mod hash;
 mod instance;
+mod name_index;
 mod viewer;
 
 pub use rbx_types as types;
 
 pub use crate::{
     dom::WeakDom,
-    instance::{Instance, InstanceBuilder},
+    instance::{Instance, InstanceBuilder, RawProperty},
+    name_index::NameIndex,
     viewer::{DomViewer, ViewedInstance},
 };
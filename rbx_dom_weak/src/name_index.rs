@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use rbx_types::Ref;
+
+use crate::WeakDom;
+
+/// An index of the children of every instance in a [`WeakDom`], grouped by
+/// name. Created with [`WeakDom::build_name_index`].
+///
+/// Looking up a child by name with a `NameIndex` takes constant time instead
+/// of scanning through all of the parent's children, which adds up when
+/// resolving many paths against a large DOM.
+///
+/// A `NameIndex` is a snapshot of the DOM at the time that it was built. It is
+/// not updated when the DOM changes, so any mutation that adds, removes,
+/// moves, or renames instances invalidates it.
+#[derive(Debug, Clone, Default)]
+pub struct NameIndex {
+    children: HashMap<Ref, HashMap<String, Vec<Ref>>>,
+}
+
+impl NameIndex {
+    pub(crate) fn new(dom: &WeakDom) -> NameIndex {
+        let mut children = HashMap::new();
+        let mut to_visit = vec![dom.root_ref()];
+
+        while let Some(referent) = to_visit.pop() {
+            let instance = dom.get_by_ref(referent).unwrap();
+            let mut by_name: HashMap<String, Vec<Ref>> = HashMap::new();
+
+            for &child_ref in instance.children() {
+                let child = dom.get_by_ref(child_ref).unwrap();
+                by_name
+                    .entry(child.name.clone())
+                    .or_default()
+                    .push(child_ref);
+                to_visit.push(child_ref);
+            }
+
+            if !by_name.is_empty() {
+                children.insert(referent, by_name);
+            }
+        }
+
+        NameIndex { children }
+    }
+
+    /// Returns the referents of every child of `parent` with the given name,
+    /// in the same order that they appear in the parent.
+    pub fn get_children(&self, parent: Ref, name: &str) -> &[Ref] {
+        self.children
+            .get(&parent)
+            .and_then(|by_name| by_name.get(name))
+            .map_or(&[], |children| children.as_slice())
+    }
+
+    /// Returns the referent of the first child of `parent` with the given
+    /// name, like Roblox's `FindFirstChild`.
+    pub fn find_first_child(&self, parent: Ref, name: &str) -> Option<Ref> {
+        self.get_children(parent, name).first().copied()
+    }
+
+    /// Follows a path of names starting at `start`, picking the first child
+    /// with each name in turn. Returns `None` if any part of the path can't be
+    /// found.
+    ///
+    /// An empty path resolves to `start`.
+    pub fn resolve_path<'a, I>(&self, start: Ref, path: I) -> Option<Ref>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        path.into_iter()
+            .try_fold(start, |parent, name| self.find_first_child(parent, name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::InstanceBuilder;
+
+    #[test]
+    fn resolve_path() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let storage_ref = dom.insert(dom.root_ref(), InstanceBuilder::new("ReplicatedStorage"));
+        let first_ref = dom.insert(
+            storage_ref,
+            InstanceBuilder::new("Folder").with_name("Shared"),
+        );
+        let second_ref = dom.insert(
+            storage_ref,
+            InstanceBuilder::new("Folder").with_name("Shared"),
+        );
+        let module_ref = dom.insert(
+            first_ref,
+            InstanceBuilder::new("ModuleScript").with_name("Util"),
+        );
+
+        let index = dom.build_name_index();
+
+        assert_eq!(
+            index.resolve_path(dom.root_ref(), vec!["ReplicatedStorage", "Shared", "Util"]),
+            Some(module_ref)
+        );
+        assert_eq!(
+            index.resolve_path(dom.root_ref(), vec!["ReplicatedStorage", "Missing"]),
+            None
+        );
+        assert_eq!(
+            index.resolve_path(dom.root_ref(), Vec::new()),
+            Some(dom.root_ref())
+        );
+
+        assert_eq!(
+            index.get_children(storage_ref, "Shared"),
+            &[first_ref, second_ref]
+        );
+        assert_eq!(index.find_first_child(module_ref, "Anything"), None);
+    }
+}
@@ -4,7 +4,7 @@
 
 use std::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
 };
 
 use rbx_types::{Variant, VariantType};
@@ -39,6 +39,98 @@ impl<'a> ReflectionDatabase<'a> {
             enums: HashMap::new(),
         }
     }
+
+    /// Finds the canonical descriptor for the given property on the given
+    /// class, searching through the class's superclasses.
+    ///
+    /// If the property is an alias, like the legacy `BasePart.BrickColor` or
+    /// `Part.size`, the descriptor of the property it aliases is returned
+    /// instead. The descriptor's name can be compared to `property_name` to
+    /// tell whether the property has a different canonical name.
+    pub fn find_canonical_property(
+        &self,
+        class_name: &str,
+        property_name: &str,
+    ) -> Option<&PropertyDescriptor<'a>> {
+        self.find_canonical_property_with_class(class_name, property_name)
+            .map(|(_, descriptor)| descriptor)
+    }
+
+    /// Returns the type that the given property on the given class is stored
+    /// as in Roblox's file formats, which can be different from the type of
+    /// its canonical form. For example, `BasePart.Color` is a `Color3`, but is
+    /// stored as a `Color3uint8`.
+    ///
+    /// Returns `None` if the property is unknown or isn't serialized.
+    pub fn serialized_type(&self, class_name: &str, property_name: &str) -> Option<VariantType> {
+        let (class, canonical) =
+            self.find_canonical_property_with_class(class_name, property_name)?;
+
+        let serialized = match &canonical.kind {
+            PropertyKind::Canonical { serialization } => match serialization {
+                PropertySerialization::Serializes => canonical,
+                PropertySerialization::SerializesAs(serialized_name) => {
+                    class.properties.get(serialized_name.as_ref())?
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        match &serialized.data_type {
+            DataType::Value(ty) => Some(*ty),
+            DataType::Enum(_) => Some(VariantType::Enum),
+        }
+    }
+
+    /// Finds the default value of the given property on the given class,
+    /// searching through the class's superclasses.
+    ///
+    /// Aliases, like `Part.size`, give the default value of the property that
+    /// they alias.
+    pub fn find_default_property(&self, class_name: &str, property_name: &str) -> Option<&Variant> {
+        let canonical_name = self
+            .find_canonical_property(class_name, property_name)
+            .map_or(property_name, |descriptor| descriptor.name.as_ref());
+
+        let mut current_class = self.classes.get(class_name)?;
+
+        loop {
+            if let Some(value) = current_class.default_properties.get(canonical_name) {
+                return Some(value);
+            }
+
+            current_class = self
+                .classes
+                .get(current_class.superclass.as_ref()?.as_ref())?;
+        }
+    }
+
+    /// Finds the canonical descriptor for a property like
+    /// `find_canonical_property`, along with the class that it's declared on.
+    fn find_canonical_property_with_class(
+        &self,
+        class_name: &str,
+        property_name: &str,
+    ) -> Option<(&ClassDescriptor<'a>, &PropertyDescriptor<'a>)> {
+        let mut current_class = self.classes.get(class_name)?;
+
+        loop {
+            if let Some(descriptor) = current_class.properties.get(property_name) {
+                return match &descriptor.kind {
+                    PropertyKind::Alias { alias_for } => current_class
+                        .properties
+                        .get(alias_for.as_ref())
+                        .map(|canonical| (current_class, canonical)),
+                    _ => Some((current_class, descriptor)),
+                };
+            }
+
+            current_class = self
+                .classes
+                .get(current_class.superclass.as_ref()?.as_ref())?;
+        }
+    }
 }
 
 /// Describes a class of Instance, its properties, and its relation to other
@@ -80,6 +172,47 @@ impl<'a> ClassDescriptor<'a> {
             default_properties: HashMap::new(),
         }
     }
+
+    /// Returns all of the properties declared directly on this class, sorted
+    /// by name. Properties inherited from superclasses are not included.
+    pub fn properties_sorted(&self) -> Vec<&PropertyDescriptor<'a>> {
+        let mut properties: Vec<_> = self.properties.values().collect();
+        properties.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        properties
+    }
+
+    /// Returns all of the properties declared directly on this class, grouped
+    /// by their [category][PropertyDescriptor::category] and sorted by name
+    /// within each group.
+    pub fn properties_by_category(&self) -> BTreeMap<&str, Vec<&PropertyDescriptor<'a>>> {
+        let mut categories: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+
+        for property in self.properties_sorted() {
+            categories
+                .entry(property.category())
+                .or_default()
+                .push(property);
+        }
+
+        categories
+    }
+
+    /// Tells whether this class is a service, like `Workspace` or `Lighting`.
+    /// Services are singletons that live directly under the `DataModel`.
+    pub fn is_service(&self) -> bool {
+        self.tags.contains(&ClassTag::Service)
+    }
+
+    /// Tells whether instances of this class can be created with
+    /// `Instance.new`.
+    pub fn is_creatable(&self) -> bool {
+        !self.tags.contains(&ClassTag::NotCreatable)
+    }
+
+    /// Tells whether this class has been deprecated by Roblox.
+    pub fn is_deprecated(&self) -> bool {
+        self.tags.contains(&ClassTag::Deprecated)
+    }
 }
 
 /// Describes a property on an Instance.
@@ -102,6 +235,22 @@ pub struct PropertyDescriptor<'a> {
 
     /// The kind of property this is, including whether it is canonical.
     pub kind: PropertyKind<'a>,
+
+    /// The security contexts required to read and write this property, if
+    /// they're known.
+    ///
+    /// The database bundled in rbx_reflection_database was generated before
+    /// this field existed, so it is `None` there until the next regeneration.
+    #[serde(default)]
+    pub security: Option<PropertySecurity>,
+
+    /// The category that this property is grouped under in Roblox Studio's
+    /// property grid, like "Appearance" or "Behavior", if it's known.
+    ///
+    /// Like `security`, this is `None` in the bundled database until it is
+    /// regenerated.
+    #[serde(default)]
+    pub category: Option<Cow<'a, str>>,
 }
 
 impl<'a> PropertyDescriptor<'a> {
@@ -115,6 +264,33 @@ impl<'a> PropertyDescriptor<'a> {
             kind: PropertyKind::Canonical {
                 serialization: PropertySerialization::Serializes,
             },
+            security: None,
+            category: None,
+        }
+    }
+
+    /// Returns the category that this property is grouped under in Roblox
+    /// Studio's property grid. Properties without a known category are
+    /// grouped under "Data".
+    pub fn category(&self) -> &str {
+        self.category.as_deref().unwrap_or("Data")
+    }
+
+    /// Tells whether Lua scripts can access this property in any way.
+    #[allow(clippy::match_like_matches_macro)]
+    pub fn is_scriptable(&self) -> bool {
+        match self.scriptability {
+            Scriptability::None => false,
+            _ => true,
+        }
+    }
+
+    /// Tells whether Lua scripts can assign to this property.
+    #[allow(clippy::match_like_matches_macro)]
+    pub fn is_writable(&self) -> bool {
+        match self.scriptability {
+            Scriptability::ReadWrite | Scriptability::Write => true,
+            _ => false,
         }
     }
 }
@@ -180,6 +356,50 @@ pub enum Scriptability {
     Custom,
 }
 
+/// The security contexts that a script must run in to read and write a
+/// property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+#[non_exhaustive]
+pub struct PropertySecurity {
+    /// The security context required to read the property.
+    pub read: Security,
+
+    /// The security context required to write the property.
+    pub write: Security,
+}
+
+impl PropertySecurity {
+    /// Creates a new `PropertySecurity` with the given read and write
+    /// security contexts.
+    pub fn new(read: Security, write: Security) -> Self {
+        Self { read, write }
+    }
+}
+
+/// A security context that Roblox uses to restrict access to members.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Security {
+    /// Any script can access the member.
+    None,
+
+    /// Only the command bar and Roblox's scripts can access the member.
+    LocalUserSecurity,
+
+    /// Plugins, the command bar, and Roblox's scripts can access the member.
+    PluginSecurity,
+
+    /// Only Roblox's scripts can access the member.
+    RobloxScriptSecurity,
+
+    /// No scripts can access the member.
+    NotAccessibleSecurity,
+
+    /// Only Roblox itself can access the member.
+    RobloxSecurity,
+}
+
 /// Describes a Roblox enum and all of its items.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -16,8 +16,115 @@ pub fn get() -> &'static ReflectionDatabase<'static> {
 mod test {
     use super::*;
 
+    use rbx_types::{Variant, VariantType};
+
     #[test]
     fn smoke_test() {
         let _database = get();
     }
+
+    #[test]
+    fn class_tags() {
+        let database = get();
+
+        let workspace = &database.classes["Workspace"];
+        assert!(workspace.is_service());
+        assert!(!workspace.is_creatable());
+        assert!(!workspace.is_deprecated());
+
+        let instance = &database.classes["Instance"];
+        assert!(!instance.is_service());
+        assert!(!instance.is_creatable());
+
+        let folder = &database.classes["Folder"];
+        assert!(!folder.is_service());
+        assert!(folder.is_creatable());
+
+        assert!(database.classes["Hopper"].is_deprecated());
+    }
+
+    #[test]
+    fn property_scriptability() {
+        let base_part = &get().classes["BasePart"];
+
+        let anchored = &base_part.properties["Anchored"];
+        assert!(anchored.is_scriptable());
+        assert!(anchored.is_writable());
+
+        let mass = &base_part.properties["Mass"];
+        assert!(mass.is_scriptable());
+        assert!(!mass.is_writable());
+
+        let color3uint8 = &base_part.properties["Color3uint8"];
+        assert!(!color3uint8.is_scriptable());
+        assert!(!color3uint8.is_writable());
+    }
+
+    #[test]
+    fn properties_sorted() {
+        let base_part = &get().classes["BasePart"];
+        let names: Vec<&str> = base_part
+            .properties_sorted()
+            .iter()
+            .map(|property| property.name.as_ref())
+            .collect();
+
+        assert_eq!(names.len(), base_part.properties.len());
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(names.contains(&"Anchored"));
+        assert!(names.contains(&"Size"));
+    }
+
+    #[test]
+    fn properties_by_category() {
+        let mut base_part = get().classes["BasePart"].clone();
+        base_part.properties.get_mut("Anchored").unwrap().category = Some("Behavior".into());
+        base_part.properties.get_mut("CanCollide").unwrap().category = Some("Behavior".into());
+
+        let categories = base_part.properties_by_category();
+
+        let behavior: Vec<&str> = categories["Behavior"]
+            .iter()
+            .map(|property| property.name.as_ref())
+            .collect();
+        assert_eq!(behavior, vec!["Anchored", "CanCollide"]);
+
+        let data = &categories["Data"];
+        assert_eq!(data.len(), base_part.properties.len() - 2);
+        assert!(data.iter().any(|property| property.name == "Size"));
+    }
+
+    #[test]
+    fn serialized_type() {
+        let database = get();
+
+        assert_eq!(
+            database.serialized_type("Part", "Color"),
+            Some(VariantType::Color3uint8)
+        );
+        assert_eq!(
+            database.serialized_type("Part", "Transparency"),
+            Some(VariantType::Float32)
+        );
+        assert_eq!(
+            database.serialized_type("Part", "Material"),
+            Some(VariantType::Enum)
+        );
+        assert_eq!(database.serialized_type("Part", "Missing"), None);
+    }
+
+    #[test]
+    fn find_default_property() {
+        let database = get();
+
+        assert_eq!(
+            database.find_default_property("Part", "Anchored"),
+            Some(&Variant::Bool(false))
+        );
+        assert_eq!(
+            database.find_default_property("Part", "size"),
+            database.find_default_property("Part", "Size")
+        );
+        assert_eq!(database.find_default_property("Part", "Missing"), None);
+    }
 }
@@ -1,3 +1,5 @@
+use std::ops::{Add, Mul, Sub};
+
 /// Represents any Roblox enum value.
 ///
 /// Roblox enums are not strongly typed, so the meaning of a value depends on
@@ -5,7 +7,7 @@
 ///
 /// A list of all enums and their values are available [on the Roblox Developer
 /// Hub](https://developer.roblox.com/en-us/api-reference/enum).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde",
     derive(serde::Serialize, serde::Deserialize),
@@ -42,6 +44,97 @@ impl Vector2 {
     }
 }
 
+/// Implements the arithmetic operators and component-wise helpers shared by
+/// `Vector2` and `Vector3`.
+macro_rules! impl_vector_math {
+    ($name:ident { $($field:ident),+ }) => {
+        impl $name {
+            /// Returns a vector containing the smallest value of each component
+            /// of `self` and `other`.
+            pub fn component_min(self, other: Self) -> Self {
+                Self {
+                    $($field: self.$field.min(other.$field),)+
+                }
+            }
+
+            /// Returns a vector containing the largest value of each component
+            /// of `self` and `other`.
+            pub fn component_max(self, other: Self) -> Self {
+                Self {
+                    $($field: self.$field.max(other.$field),)+
+                }
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self {
+                    $($field: self.$field + other.$field,)+
+                }
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self {
+                    $($field: self.$field - other.$field,)+
+                }
+            }
+        }
+
+        impl Mul<f32> for $name {
+            type Output = Self;
+
+            fn mul(self, factor: f32) -> Self {
+                Self {
+                    $($field: self.$field * factor,)+
+                }
+            }
+        }
+    };
+}
+
+impl_vector_math!(Vector2 { x, y });
+impl_vector_math!(Vector3 { x, y, z });
+
+/// Implements conversions between `Vector2` or `Vector3` and arrays or tuples
+/// of their components, which makes passing them to other math libraries
+/// easier.
+macro_rules! impl_vector_conversions {
+    ($name:ident, $len:literal, ($($field:ident: $ty:ty),+)) => {
+        impl From<[f32; $len]> for $name {
+            fn from([$($field),+]: [f32; $len]) -> Self {
+                Self { $($field),+ }
+            }
+        }
+
+        impl From<$name> for [f32; $len] {
+            fn from(value: $name) -> Self {
+                [$(value.$field),+]
+            }
+        }
+
+        impl From<($($ty),+)> for $name {
+            fn from(($($field),+): ($($ty),+)) -> Self {
+                Self { $($field),+ }
+            }
+        }
+
+        impl From<$name> for ($($ty),+) {
+            fn from(value: $name) -> Self {
+                ($(value.$field),+)
+            }
+        }
+    };
+}
+
+impl_vector_conversions!(Vector2, 2, (x: f32, y: f32));
+impl_vector_conversions!(Vector3, 3, (x: f32, y: f32, z: f32));
+
 /// A version of [`Vector2`][Vector2] whose coordinates are signed 16-bit
 /// integers.
 ///
@@ -50,7 +143,7 @@ impl Vector2 {
 /// * [Vector2int16 on Roblox Developer Hub](https://developer.roblox.com/en-us/api-reference/datatype/Vector2int16)
 ///
 /// [Vector2]: struct.Vector2.html
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Vector2int16 {
     pub x: i16,
     pub y: i16,
@@ -131,7 +224,7 @@ impl Vector3 {
 /// * [Vector3int16 on Roblox Developer Hub](https://developer.roblox.com/en-us/api-reference/datatype/Vector3int16)
 ///
 /// [Vector3]: struct.Vector3.html
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Vector3int16 {
     pub x: i16,
     pub y: i16,
@@ -166,6 +259,89 @@ impl CFrame {
             orientation,
         }
     }
+
+    /// Creates a `CFrame` at the given position with no rotation.
+    pub fn from_position(position: Vector3) -> Self {
+        Self {
+            position,
+            orientation: Matrix3::identity(),
+        }
+    }
+
+    /// Creates a `CFrame` at `eye` that faces toward `target`, like Roblox's
+    /// `CFrame.lookAt`. The `up` vector decides which way the top of the
+    /// `CFrame` faces, and is usually `(0, 1, 0)`.
+    ///
+    /// The result isn't meaningful if `eye` and `target` are the same point, or
+    /// if the direction between them is parallel to `up`.
+    pub fn look_at(eye: Vector3, target: Vector3, up: Vector3) -> Self {
+        let look = normalize(target - eye);
+        let right = normalize(cross(look, up));
+        let up = cross(right, look);
+
+        Self {
+            position: eye,
+            // The columns of the orientation are the right, up, and back
+            // vectors, since CFrames look down their negative Z axis.
+            orientation: Matrix3::new(right, up, look * -1.0).transpose(),
+        }
+    }
+
+    /// Creates a `CFrame` from its position followed by the rows of its
+    /// orientation, like Roblox's `CFrame.new(x, y, z, R00, R01, R02, R10,
+    /// R11, R12, R20, R21, R22)`. This is the inverse of
+    /// [`CFrame::components`].
+    pub fn from_components(components: [f32; 12]) -> Self {
+        let [x, y, z, r00, r01, r02, r10, r11, r12, r20, r21, r22] = components;
+
+        Self {
+            position: Vector3::new(x, y, z),
+            orientation: Matrix3::new(
+                Vector3::new(r00, r01, r02),
+                Vector3::new(r10, r11, r12),
+                Vector3::new(r20, r21, r22),
+            ),
+        }
+    }
+
+    /// Returns this `CFrame`'s position followed by the rows of its
+    /// orientation, in the same order as Roblox's `CFrame:GetComponents()`.
+    pub fn components(&self) -> [f32; 12] {
+        let Self {
+            position,
+            orientation,
+        } = self;
+
+        [
+            position.x,
+            position.y,
+            position.z,
+            orientation.x.x,
+            orientation.x.y,
+            orientation.x.z,
+            orientation.y.x,
+            orientation.y.y,
+            orientation.y.z,
+            orientation.z.x,
+            orientation.z.y,
+            orientation.z.z,
+        ]
+    }
+
+    /// Returns a copy of this `CFrame` with the same position and its
+    /// orientation re-orthonormalized. See [`Matrix3::orthonormalize`].
+    pub fn orthonormalize(&self) -> Self {
+        Self {
+            position: self.position,
+            orientation: self.orientation.orthonormalize(),
+        }
+    }
+
+    /// Tells whether this `CFrame`'s orientation is orthonormal, within the
+    /// given tolerance. See [`Matrix3::is_orthonormal`].
+    pub fn is_orthonormal(&self, epsilon: f32) -> bool {
+        self.orientation.is_orthonormal(epsilon)
+    }
 }
 
 /// Used to represent the `orientation` field of `CFrame` and not a standalone
@@ -197,6 +373,69 @@ impl Matrix3 {
             z: Vector3::new(self.x.z, self.y.z, self.z.z),
         }
     }
+
+    /// Returns a matrix whose columns are orthogonal unit vectors, found by
+    /// applying the Gram-Schmidt process to the columns of this matrix.
+    ///
+    /// The first column keeps its direction, the second is made perpendicular
+    /// to the first, and the third is made perpendicular to both. This is
+    /// useful for cleaning up rotations that have drifted slightly due to
+    /// floating point error, which Roblox may refuse to load.
+    ///
+    /// A column that is zero, or that depends on the columns before it, has
+    /// no direction to keep and becomes a zero vector, so the result is only
+    /// orthonormal if the columns of this matrix are linearly independent.
+    pub fn orthonormalize(&self) -> Self {
+        let columns = self.transpose();
+
+        let x = normalize(columns.x);
+        let y = normalize(columns.y - x * dot(columns.y, x));
+        let z = normalize(columns.z - x * dot(columns.z, x) - y * dot(columns.z, y));
+
+        Self { x, y, z }.transpose()
+    }
+
+    /// Tells whether the columns of this matrix are orthogonal unit vectors,
+    /// allowing each of their dot products to be off by up to `epsilon`.
+    pub fn is_orthonormal(&self, epsilon: f32) -> bool {
+        let columns = self.transpose();
+        let pairs = [
+            (columns.x, columns.x, 1.0),
+            (columns.y, columns.y, 1.0),
+            (columns.z, columns.z, 1.0),
+            (columns.x, columns.y, 0.0),
+            (columns.x, columns.z, 0.0),
+            (columns.y, columns.z, 0.0),
+        ];
+
+        pairs
+            .iter()
+            .all(|&(a, b, expected)| (dot(a, b) - expected).abs() <= epsilon)
+    }
+}
+
+fn dot(a: Vector3, b: Vector3) -> f32 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+fn cross(a: Vector3, b: Vector3) -> Vector3 {
+    Vector3::new(
+        a.y * b.z - a.z * b.y,
+        a.z * b.x - a.x * b.z,
+        a.x * b.y - a.y * b.x,
+    )
+}
+
+/// Scales a vector to unit length, leaving zero vectors as they are instead
+/// of dividing by zero.
+fn normalize(vector: Vector3) -> Vector3 {
+    let length = dot(vector, vector).sqrt();
+
+    if length == 0.0 {
+        vector
+    } else {
+        vector * (1.0 / length)
+    }
 }
 
 /// Represents any color, including HDR colors.
@@ -238,7 +477,7 @@ impl From<Color3uint8> for Color3 {
 ///   colors.
 ///
 /// [BasePart.Color]: https://developer.roblox.com/en-us/api-reference/property/BasePart/Color
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Color3uint8 {
     pub r: u8,
     pub g: u8,
@@ -311,7 +550,7 @@ impl Region3 {
 /// * [Region3int16 on Roblox Developer Hub](https://developer.roblox.com/en-us/api-reference/datatype/Region3int16)
 ///
 /// [Region3]: struct.Region3.html
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Region3int16 {
     pub min: Vector3int16,
     pub max: Vector3int16,
@@ -371,6 +610,33 @@ impl UDim2 {
     pub fn new(x: UDim, y: UDim) -> Self {
         Self { x, y }
     }
+
+    /// Creates a `UDim2` from the scale and offset of each axis, like Roblox's
+    /// `UDim2.new(xScale, xOffset, yScale, yOffset)`.
+    pub fn from_components(x_scale: f32, x_offset: i32, y_scale: f32, y_offset: i32) -> Self {
+        Self {
+            x: UDim::new(x_scale, x_offset),
+            y: UDim::new(y_scale, y_offset),
+        }
+    }
+
+    /// Creates a `UDim2` with the given scales and no offsets, like Roblox's
+    /// `UDim2.fromScale`.
+    pub fn from_scale(x_scale: f32, y_scale: f32) -> Self {
+        Self::from_components(x_scale, 0, y_scale, 0)
+    }
+
+    /// Creates a `UDim2` with the given offsets and no scales, like Roblox's
+    /// `UDim2.fromOffset`.
+    pub fn from_offset(x_offset: i32, y_offset: i32) -> Self {
+        Self::from_components(0.0, x_offset, 0.0, y_offset)
+    }
+}
+
+impl From<(UDim, UDim)> for UDim2 {
+    fn from((x, y): (UDim, UDim)) -> Self {
+        Self { x, y }
+    }
 }
 
 /// A range between two numbers.
@@ -564,3 +830,170 @@ mod serde_test {
         );
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn color3uint8_round_trip() {
+        for &value in &[0, 128, 255] {
+            let original = Color3uint8::new(value, value, value);
+            let converted: Color3uint8 = Color3::from(original).into();
+
+            assert_eq!(converted, original);
+        }
+    }
+
+    #[test]
+    fn orthonormalize_skewed() {
+        let skewed = CFrame::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Matrix3::new(
+                Vector3::new(1.0, 0.02, 0.0),
+                Vector3::new(0.01, 0.99, -0.03),
+                Vector3::new(0.0, 0.01, 1.02),
+            ),
+        );
+        assert!(!skewed.is_orthonormal(1e-4));
+
+        let fixed = skewed.orthonormalize();
+        assert!(fixed.is_orthonormal(1e-5));
+        assert_eq!(fixed.position, skewed.position);
+
+        let identity = CFrame::new(Vector3::new(0.0, 0.0, 0.0), Matrix3::identity());
+        assert!(identity.is_orthonormal(0.0));
+        assert_eq!(identity.orthonormalize(), identity);
+    }
+
+    #[test]
+    fn orthonormalize_degenerate() {
+        let flat = Matrix3::new(
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.0),
+        );
+
+        let fixed = flat.orthonormalize();
+        assert_eq!(fixed, flat);
+        assert!(!fixed.is_orthonormal(1e-5));
+    }
+
+    #[test]
+    fn vector3_bounds() {
+        let points = [
+            Vector3::new(1.0, -2.0, 3.0),
+            Vector3::new(-4.0, 5.0, 0.5),
+            Vector3::new(2.0, 0.0, -6.0),
+        ];
+
+        let (min, max) = points[1..]
+            .iter()
+            .fold((points[0], points[0]), |(min, max), &point| {
+                (min.component_min(point), max.component_max(point))
+            });
+
+        assert_eq!(min, Vector3::new(-4.0, -2.0, -6.0));
+        assert_eq!(max, Vector3::new(2.0, 5.0, 3.0));
+        assert_eq!((min + max) * 0.5, Vector3::new(-1.0, 1.5, -1.5));
+        assert_eq!(max - min, Vector3::new(6.0, 7.0, 9.0));
+    }
+
+    #[test]
+    fn vector2_math() {
+        let a = Vector2::new(1.0, 4.0);
+        let b = Vector2::new(3.0, 2.0);
+
+        assert_eq!(a + b, Vector2::new(4.0, 6.0));
+        assert_eq!(a - b, Vector2::new(-2.0, 2.0));
+        assert_eq!(a * 2.0, Vector2::new(2.0, 8.0));
+        assert_eq!(a.component_min(b), Vector2::new(1.0, 2.0));
+        assert_eq!(a.component_max(b), Vector2::new(3.0, 4.0));
+    }
+
+    #[test]
+    fn vector_conversions() {
+        let vector2 = Vector2::new(1.0, 2.0);
+        assert_eq!(Vector2::from([1.0, 2.0]), vector2);
+        assert_eq!(Vector2::from((1.0, 2.0)), vector2);
+        assert_eq!(<[f32; 2]>::from(vector2), [1.0, 2.0]);
+        assert_eq!(<(f32, f32)>::from(vector2), (1.0, 2.0));
+
+        let vector3 = Vector3::new(1.0, 2.0, 3.0);
+        assert_eq!(Vector3::from([1.0, 2.0, 3.0]), vector3);
+        assert_eq!(Vector3::from((1.0, 2.0, 3.0)), vector3);
+        assert_eq!(<[f32; 3]>::from(vector3), [1.0, 2.0, 3.0]);
+        assert_eq!(<(f32, f32, f32)>::from(vector3), (1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn components() {
+        // CFrame.lookAt(Vector3.new(1, 2, 3), Vector3.new(1, 2, 10))
+        let cframe = CFrame::look_at(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(1.0, 2.0, 10.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+        let components = [1.0, 2.0, 3.0, -1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, -1.0];
+
+        assert_eq!(cframe.components(), components);
+        assert_eq!(CFrame::from_components(components), cframe);
+    }
+
+    #[test]
+    fn from_position() {
+        let cframe = CFrame::from_position(Vector3::new(1.0, 2.0, 3.0));
+
+        assert_eq!(cframe.position, Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(cframe.orientation, Matrix3::identity());
+    }
+
+    #[test]
+    fn look_at() {
+        let up = Vector3::new(0.0, 1.0, 0.0);
+
+        // Looking down the negative Z axis needs no rotation.
+        let forward = CFrame::look_at(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(1.0, 2.0, -10.0),
+            up,
+        );
+        assert_eq!(forward.position, Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(forward.orientation, Matrix3::identity());
+
+        // Looking down the positive X axis is a 90 degree turn to the right,
+        // so the back vector points along negative X and the right vector
+        // points along positive Z.
+        let right = CFrame::look_at(Vector3::new(0.0, 0.0, 0.0), Vector3::new(5.0, 0.0, 0.0), up);
+        assert_eq!(
+            right.orientation,
+            Matrix3::new(
+                Vector3::new(0.0, 0.0, -1.0),
+                Vector3::new(0.0, 1.0, 0.0),
+                Vector3::new(1.0, 0.0, 0.0),
+            )
+        );
+        assert!(right.is_orthonormal(1e-6));
+    }
+
+    #[test]
+    fn udim2_components() {
+        let udim2 = UDim2::from_components(0.5, 10, 1.0, -20);
+
+        assert_eq!(udim2.x, UDim::new(0.5, 10));
+        assert_eq!(udim2.y, UDim::new(1.0, -20));
+        assert_eq!(
+            UDim2::from((UDim::new(0.5, 10), UDim::new(1.0, -20))),
+            udim2
+        );
+
+        assert_eq!(
+            UDim2::from_scale(0.5, 1.0),
+            UDim2::from_components(0.5, 0, 1.0, 0)
+        );
+        assert_eq!(
+            UDim2::from_offset(10, -20),
+            UDim2::from_components(0.0, 10, 0.0, -20)
+        );
+    }
+}
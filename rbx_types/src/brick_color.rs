@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::Color3uint8;
+
 macro_rules! make_brick_color {
     ({
         $([
@@ -15,7 +17,7 @@ macro_rules! make_brick_color {
         ///
         /// Parts no longer use BrickColor, but we have conversions here to
         /// support older models.
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
         #[repr(u16)]
         #[non_exhaustive]
         pub enum BrickColor {
@@ -62,6 +64,17 @@ macro_rules! make_brick_color {
                     _ => None,
                 }
             }
+
+            /// Returns the color that this BrickColor represents.
+            pub fn to_color3uint8(self) -> Color3uint8 {
+                match self {
+                    $(
+                        BrickColor::$enum => {
+                            Color3uint8::new($color3_r, $color3_g, $color3_b)
+                        }
+                    )+
+                }
+            }
         }
 
         impl fmt::Display for BrickColor {
@@ -335,6 +348,18 @@ mod tests {
     fn from_number() {
         assert_eq!(BrickColor::from_number(1030), Some(BrickColor::PastelBrown));
     }
+
+    #[test]
+    fn to_color3uint8() {
+        assert_eq!(
+            BrickColor::BrightRed.to_color3uint8(),
+            Color3uint8::new(196, 40, 28)
+        );
+        assert_eq!(
+            BrickColor::White.to_color3uint8(),
+            Color3uint8::new(242, 243, 243)
+        );
+    }
 }
 
 #[cfg(all(test, feature = "serde"))]
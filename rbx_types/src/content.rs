@@ -1,6 +1,10 @@
+use crate::Ref;
+
 /// A reference to a Roblox asset.
 ///
-/// When exposed to Lua, this is just a string.
+/// Usually this is a URL, and when exposed to Lua, it's just a string. Newer
+/// content properties can instead point to an instance, like an
+/// `EditableImage`, which is represented by [`Content::from_object`].
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde",
@@ -9,23 +13,90 @@
 )]
 pub struct Content {
     url: String,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    object: Option<Ref>,
 }
 
 impl Content {
     #[inline]
     pub fn new() -> Self {
-        Content { url: String::new() }
+        Content {
+            url: String::new(),
+            object: None,
+        }
+    }
+
+    /// Creates a `Content` that points to the instance with the given
+    /// referent instead of a URL. Its URL is empty.
+    ///
+    /// The object is not kept when a `Content` is serialized with serde.
+    pub fn from_object(referent: Ref) -> Self {
+        Content {
+            url: String::new(),
+            object: Some(referent),
+        }
+    }
+
+    /// Returns the referent of the instance that this `Content` points to, if
+    /// it was created with [`Content::from_object`].
+    #[inline]
+    pub fn as_object(&self) -> Option<Ref> {
+        self.object
     }
 
     #[inline]
     pub fn into_string(self) -> String {
         self.url
     }
+
+    /// Creates a `Content` pointing to the asset with the given ID, in the
+    /// form `rbxassetid://<id>`.
+    pub fn from_asset_id(id: u64) -> Self {
+        Content {
+            url: format!("rbxassetid://{}", id),
+            object: None,
+        }
+    }
+
+    /// Returns the ID of the asset this `Content` points to, if it refers to
+    /// one using either the `rbxassetid://<id>` form or the
+    /// `https://www.roblox.com/asset/?id=<id>` form.
+    pub fn as_asset_id(&self) -> Option<u64> {
+        let url = self.url.trim();
+
+        if let Some(id) = strip_prefix(url, "rbxassetid://") {
+            return id.parse().ok();
+        }
+
+        let rest = strip_prefix(url, "https://").or_else(|| strip_prefix(url, "http://"))?;
+        let rest = strip_prefix(rest, "www.").unwrap_or(rest);
+        let query = strip_prefix(rest, "roblox.com/asset/?")
+            .or_else(|| strip_prefix(rest, "roblox.com/asset?"))?;
+
+        query
+            .split('&')
+            .find_map(|pair| strip_prefix(pair, "id="))?
+            .parse()
+            .ok()
+    }
+}
+
+/// Returns the rest of `value` after `prefix`, if `value` starts with it.
+///
+/// `str::strip_prefix` does the same thing, but needs Rust 1.45.
+#[allow(clippy::manual_strip)]
+fn strip_prefix<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    if value.starts_with(prefix) {
+        Some(&value[prefix.len()..])
+    } else {
+        None
+    }
 }
 
 impl From<String> for Content {
     fn from(url: String) -> Self {
-        Self { url }
+        Self { url, object: None }
     }
 }
 
@@ -33,6 +104,7 @@ impl From<&'_ str> for Content {
     fn from(url: &str) -> Self {
         Self {
             url: url.to_owned(),
+            object: None,
         }
     }
 }
@@ -60,3 +132,50 @@ impl AsMut<String> for Content {
         &mut self.url
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn asset_id() {
+        assert_eq!(Content::from("rbxassetid://123").as_asset_id(), Some(123));
+        assert_eq!(
+            Content::from("http://www.roblox.com/asset/?id=456").as_asset_id(),
+            Some(456)
+        );
+        assert_eq!(
+            Content::from("https://www.roblox.com/asset/?id=789&version=2").as_asset_id(),
+            Some(789)
+        );
+    }
+
+    #[test]
+    fn not_asset_id() {
+        assert_eq!(Content::new().as_asset_id(), None);
+        assert_eq!(
+            Content::from("rbxasset://textures/face.png").as_asset_id(),
+            None
+        );
+        assert_eq!(Content::from("rbxassetid://abc").as_asset_id(), None);
+    }
+
+    #[test]
+    fn object() {
+        let referent = Ref::new();
+        let content = Content::from_object(referent);
+
+        assert_eq!(content.as_object(), Some(referent));
+        assert_eq!(content.as_ref() as &str, "");
+        assert_ne!(content, Content::new());
+        assert_eq!(Content::from("rbxassetid://1").as_object(), None);
+    }
+
+    #[test]
+    fn from_asset_id() {
+        let content = Content::from_asset_id(123);
+
+        assert_eq!(content.as_ref() as &str, "rbxassetid://123");
+        assert_eq!(content.as_asset_id(), Some(123));
+    }
+}
@@ -42,16 +42,24 @@ impl Ref {
     }
 }
 
+/// Formats a `Ref` as 32 lowercase hexadecimal digits. A `Ref` that points to
+/// nothing is formatted as all zeroes.
 impl fmt::Display for Ref {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(formatter, "{:032x}", self.value())
     }
 }
 
+/// Parses a `Ref` from the format produced by its `Display` implementation.
+/// The string `null` is also accepted as a `Ref` that points to nothing.
 impl FromStr for Ref {
     type Err = std::num::ParseIntError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input == "null" {
+            return Ok(Ref::none());
+        }
+
         let value = u128::from_str_radix(input, 16)?;
 
         Ok(Ref(NonZeroU128::new(value)))
@@ -148,6 +156,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn from_str_null() {
+        assert_eq!(Ref::from_str("null").unwrap(), Ref::none());
+        assert!(Ref::from_str("nil").is_err());
+    }
+
+    #[test]
+    fn string_round_trip() {
+        for &referent in &[Ref::new(), Ref::new(), Ref::none()] {
+            assert_eq!(Ref::from_str(&referent.to_string()).unwrap(), referent);
+        }
+    }
+
     #[test]
     fn size() {
         assert_eq!(std::mem::size_of::<Ref>(), std::mem::size_of::<u128>());
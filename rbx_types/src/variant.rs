@@ -1,3 +1,10 @@
+use std::{
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
 use crate::{
     Axes, BinaryString, BrickColor, CFrame, Color3, Color3uint8, ColorSequence, Content, Enum,
     Faces, NumberRange, NumberSequence, PhysicalProperties, Ray, Rect, Ref, Region3, Region3int16,
@@ -36,6 +43,7 @@ macro_rules! make_variant {
         }
 
         impl Variant {
+            /// Returns the type of the value contained in this `Variant`.
             pub fn ty(&self) -> VariantType {
                 match self {
                     $(
@@ -66,6 +74,34 @@ macro_rules! make_variant {
             )*
         }
 
+        impl VariantType {
+            /// Returns the canonical name of this type, like `"Vector3"`.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(
+                        VariantType::$variant_name => stringify!($variant_name),
+                    )*
+                }
+            }
+        }
+
+        impl FromStr for VariantType {
+            type Err = ParseVariantTypeError;
+
+            /// Parses a `VariantType` from its canonical name, as returned by
+            /// [`VariantType::as_str`].
+            fn from_str(name: &str) -> Result<Self, Self::Err> {
+                match name {
+                    $(
+                        stringify!($variant_name) => Ok(VariantType::$variant_name),
+                    )*
+                    _ => Err(ParseVariantTypeError {
+                        name: name.to_owned(),
+                    }),
+                }
+            }
+        }
+
         #[cfg(test)]
         mod generated_test {
             use super::*;
@@ -82,6 +118,18 @@ macro_rules! make_variant {
                 $( trait_test::<$inner_type>(); )*
                 trait_test::<SharedString>();
             }
+
+            /// Makes sure that every `VariantType` survives a round trip
+            /// through its name.
+            #[test]
+            fn type_names_round_trip() {
+                $(
+                    assert_eq!(
+                        VariantType::$variant_name.as_str().parse::<VariantType>().unwrap(),
+                        VariantType::$variant_name,
+                    );
+                )*
+            }
         }
     };
 }
@@ -128,12 +176,436 @@ make_variant! {
     OptionalCFrame(Option<CFrame>),
 }
 
+/// Generates accessors on `Variant` that return the value inside of a
+/// specific variant, or `None` if the `Variant` holds a different type.
+macro_rules! variant_accessors {
+    ( $( $(#[$attr:meta])* $method:ident -> $variant_name:ident ( $inner_type:ty ), )* ) => {
+        impl Variant {
+            $(
+                $(#[$attr])*
+                #[inline]
+                pub fn $method(&self) -> Option<$inner_type> {
+                    match self {
+                        Variant::$variant_name(value) => Some(*value),
+                        _ => None,
+                    }
+                }
+            )*
+        }
+    };
+}
+
+variant_accessors! {
+    /// Returns the value inside of this `Variant` if it is a `Bool`.
+    as_bool -> Bool(bool),
+
+    /// Returns the value inside of this `Variant` if it is a `Float32`.
+    as_f32 -> Float32(f32),
+
+    /// Returns the value inside of this `Variant` if it is a `Float64`.
+    as_f64 -> Float64(f64),
+
+    /// Returns the value inside of this `Variant` if it is an `Int32`.
+    as_i32 -> Int32(i32),
+
+    /// Returns the value inside of this `Variant` if it is an `Int64`.
+    as_i64 -> Int64(i64),
+
+    /// Returns the value inside of this `Variant` if it is a `Vector3`.
+    as_vector3 -> Vector3(Vector3),
+
+    /// Returns the value inside of this `Variant` if it is a `Ref`.
+    as_ref -> Ref(Ref),
+}
+
+impl Variant {
+    /// Returns the value inside of this `Variant` if it is a `String`.
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Variant::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns this value as a `Color3` if it holds either a `Color3` or a
+    /// `Color3uint8`, converting between the two as needed.
+    pub fn coerce_color3(&self) -> Option<Color3> {
+        match self {
+            Variant::Color3(value) => Some(*value),
+            Variant::Color3uint8(value) => Some((*value).into()),
+            _ => None,
+        }
+    }
+
+    /// Returns this value as a `Color3uint8` if it holds either a `Color3` or a
+    /// `Color3uint8`, converting between the two as needed. Channels of a
+    /// `Color3` outside of the range 0 to 1 are clamped.
+    pub fn coerce_color3uint8(&self) -> Option<Color3uint8> {
+        match self {
+            Variant::Color3(value) => Some((*value).into()),
+            Variant::Color3uint8(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Tells whether two values are equal, comparing floating point numbers by
+    /// their bits instead of by their numeric value. Unlike `==`, this treats
+    /// `NaN` as equal to itself when the bits match, and `0.0` as different
+    /// from `-0.0`.
+    ///
+    /// This applies to `Float32`, `Float64`, and the float components of
+    /// `CFrame`, `OptionalCFrame`, `Color3`, `ColorSequence`, `NumberRange`,
+    /// `NumberSequence`, `PhysicalProperties`, `Ray`, `Rect`, `Region3`,
+    /// `UDim`, `UDim2`, `Vector2`, and `Vector3`. Other types are compared
+    /// with `==`.
+    pub fn bitwise_eq(&self, other: &Variant) -> bool {
+        match (self, other) {
+            (Variant::Float32(a), Variant::Float32(b)) => a.to_bits() == b.to_bits(),
+            (Variant::Float64(a), Variant::Float64(b)) => a.to_bits() == b.to_bits(),
+            (Variant::CFrame(a), Variant::CFrame(b)) => cframe_bits(a) == cframe_bits(b),
+            (Variant::OptionalCFrame(a), Variant::OptionalCFrame(b)) => {
+                a.as_ref().map(cframe_bits) == b.as_ref().map(cframe_bits)
+            }
+            (Variant::Color3(a), Variant::Color3(b)) => color3_bits(a) == color3_bits(b),
+            (Variant::ColorSequence(a), Variant::ColorSequence(b)) => {
+                color_sequence_bits(a) == color_sequence_bits(b)
+            }
+            (Variant::NumberRange(a), Variant::NumberRange(b)) => {
+                number_range_bits(a) == number_range_bits(b)
+            }
+            (Variant::NumberSequence(a), Variant::NumberSequence(b)) => {
+                number_sequence_bits(a) == number_sequence_bits(b)
+            }
+            (Variant::PhysicalProperties(a), Variant::PhysicalProperties(b)) => {
+                physical_properties_bits(a) == physical_properties_bits(b)
+            }
+            (Variant::Ray(a), Variant::Ray(b)) => {
+                vector3_bits(&a.origin) == vector3_bits(&b.origin)
+                    && vector3_bits(&a.direction) == vector3_bits(&b.direction)
+            }
+            (Variant::Rect(a), Variant::Rect(b)) => {
+                vector2_bits(&a.min) == vector2_bits(&b.min)
+                    && vector2_bits(&a.max) == vector2_bits(&b.max)
+            }
+            (Variant::Region3(a), Variant::Region3(b)) => {
+                vector3_bits(&a.min) == vector3_bits(&b.min)
+                    && vector3_bits(&a.max) == vector3_bits(&b.max)
+            }
+            (Variant::UDim(a), Variant::UDim(b)) => udim_bits(a) == udim_bits(b),
+            (Variant::UDim2(a), Variant::UDim2(b)) => {
+                udim_bits(&a.x) == udim_bits(&b.x) && udim_bits(&a.y) == udim_bits(&b.y)
+            }
+            (Variant::Vector2(a), Variant::Vector2(b)) => vector2_bits(a) == vector2_bits(b),
+            (Variant::Vector3(a), Variant::Vector3(b)) => vector3_bits(a) == vector3_bits(b),
+            _ => self == other,
+        }
+    }
+
+    /// Feeds this value into the given `Hasher` so that values which are
+    /// equal according to [`Variant::bitwise_eq`] hash the same. Floating
+    /// point numbers are hashed by their bits.
+    ///
+    /// `Ref` values are hashed by their referent, as are `Content` values that
+    /// point to an instance.
+    pub fn bitwise_hash<H: Hasher>(&self, state: &mut H) {
+        self.ty().as_str().hash(state);
+
+        match self {
+            Variant::Axes(value) => value.hash(state),
+            Variant::BinaryString(value) => value.hash(state),
+            Variant::Bool(value) => value.hash(state),
+            Variant::BrickColor(value) => value.hash(state),
+            Variant::CFrame(value) => cframe_bits(value).hash(state),
+            Variant::Color3(value) => color3_bits(value).hash(state),
+            Variant::Color3uint8(value) => value.hash(state),
+            Variant::ColorSequence(value) => color_sequence_bits(value).hash(state),
+            Variant::Content(value) => value.hash(state),
+            Variant::Enum(value) => value.hash(state),
+            Variant::Faces(value) => value.hash(state),
+            Variant::Float32(value) => value.to_bits().hash(state),
+            Variant::Float64(value) => value.to_bits().hash(state),
+            Variant::Int32(value) => value.hash(state),
+            Variant::Int64(value) => value.hash(state),
+            Variant::NumberRange(value) => number_range_bits(value).hash(state),
+            Variant::NumberSequence(value) => number_sequence_bits(value).hash(state),
+            Variant::PhysicalProperties(value) => physical_properties_bits(value).hash(state),
+            Variant::Ray(value) => {
+                vector3_bits(&value.origin).hash(state);
+                vector3_bits(&value.direction).hash(state);
+            }
+            Variant::Rect(value) => {
+                vector2_bits(&value.min).hash(state);
+                vector2_bits(&value.max).hash(state);
+            }
+            Variant::Ref(value) => value.hash(state),
+            Variant::Region3(value) => {
+                vector3_bits(&value.min).hash(state);
+                vector3_bits(&value.max).hash(state);
+            }
+            Variant::Region3int16(value) => value.hash(state),
+            Variant::SharedString(value) => Hash::hash(value, state),
+            Variant::String(value) => value.hash(state),
+            Variant::UDim(value) => udim_bits(value).hash(state),
+            Variant::UDim2(value) => {
+                udim_bits(&value.x).hash(state);
+                udim_bits(&value.y).hash(state);
+            }
+            Variant::Vector2(value) => vector2_bits(value).hash(state),
+            Variant::Vector2int16(value) => value.hash(state),
+            Variant::Vector3(value) => vector3_bits(value).hash(state),
+            Variant::Vector3int16(value) => value.hash(state),
+            Variant::OptionalCFrame(value) => value.as_ref().map(cframe_bits).hash(state),
+        }
+    }
+}
+
+fn color3_bits(value: &Color3) -> [u32; 3] {
+    [value.r.to_bits(), value.g.to_bits(), value.b.to_bits()]
+}
+
+fn color_sequence_bits(value: &ColorSequence) -> Vec<(u32, [u32; 3])> {
+    value
+        .keypoints
+        .iter()
+        .map(|keypoint| (keypoint.time.to_bits(), color3_bits(&keypoint.color)))
+        .collect()
+}
+
+fn number_range_bits(value: &NumberRange) -> [u32; 2] {
+    [value.min.to_bits(), value.max.to_bits()]
+}
+
+fn number_sequence_bits(value: &NumberSequence) -> Vec<[u32; 3]> {
+    value
+        .keypoints
+        .iter()
+        .map(|keypoint| {
+            [
+                keypoint.time.to_bits(),
+                keypoint.value.to_bits(),
+                keypoint.envelope.to_bits(),
+            ]
+        })
+        .collect()
+}
+
+fn physical_properties_bits(value: &PhysicalProperties) -> Option<[u32; 5]> {
+    match value {
+        PhysicalProperties::Default => None,
+        PhysicalProperties::Custom(custom) => Some([
+            custom.density.to_bits(),
+            custom.friction.to_bits(),
+            custom.elasticity.to_bits(),
+            custom.friction_weight.to_bits(),
+            custom.elasticity_weight.to_bits(),
+        ]),
+    }
+}
+
+fn vector2_bits(value: &Vector2) -> [u32; 2] {
+    [value.x.to_bits(), value.y.to_bits()]
+}
+
+fn vector3_bits(value: &Vector3) -> [u32; 3] {
+    [value.x.to_bits(), value.y.to_bits(), value.z.to_bits()]
+}
+
+fn udim_bits(value: &UDim) -> (u32, i32) {
+    (value.scale.to_bits(), value.offset)
+}
+
+fn cframe_bits(value: &CFrame) -> [[u32; 3]; 4] {
+    [
+        vector3_bits(&value.position),
+        vector3_bits(&value.orientation.x),
+        vector3_bits(&value.orientation.y),
+        vector3_bits(&value.orientation.z),
+    ]
+}
+
+impl fmt::Display for VariantType {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// The error returned when parsing a [`VariantType`] from a string that isn't
+/// the name of any known type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseVariantTypeError {
+    name: String,
+}
+
+impl fmt::Display for ParseVariantTypeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "unknown variant type '{}'", self.name)
+    }
+}
+
+impl Error for ParseVariantTypeError {}
+
 impl From<&'_ str> for Variant {
     fn from(value: &str) -> Self {
         Self::String(value.to_owned())
     }
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::NumberSequenceKeypoint;
+
+    #[test]
+    fn variant_ty() {
+        assert_eq!(Variant::Bool(true).ty(), VariantType::Bool);
+        assert_eq!(Variant::from("hello").ty(), VariantType::String);
+        assert_eq!(
+            Variant::Vector3(Vector3::new(1.0, 2.0, 3.0)).ty(),
+            VariantType::Vector3
+        );
+        assert_eq!(
+            Variant::OptionalCFrame(None).ty(),
+            VariantType::OptionalCFrame
+        );
+    }
+
+    #[test]
+    fn primitive_conversions() {
+        assert_eq!(Variant::from(true), Variant::Bool(true));
+        assert_eq!(Variant::from(3.0f32), Variant::Float32(3.0));
+        assert_eq!(Variant::from(3.0f64), Variant::Float64(3.0));
+        assert_eq!(Variant::from(-7i32), Variant::Int32(-7));
+        assert_eq!(Variant::from(-7i64), Variant::Int64(-7));
+        assert_eq!(
+            Variant::from(String::from("hello")),
+            Variant::String("hello".to_owned())
+        );
+        assert_eq!(Variant::from("hello"), Variant::String("hello".to_owned()));
+    }
+
+    #[test]
+    fn accessors() {
+        assert_eq!(Variant::Bool(true).as_bool(), Some(true));
+        assert_eq!(Variant::Float32(1.0).as_bool(), None);
+
+        assert_eq!(Variant::Float32(1.5).as_f32(), Some(1.5));
+        assert_eq!(Variant::Float64(1.5).as_f32(), None);
+        assert_eq!(Variant::Float64(1.5).as_f64(), Some(1.5));
+        assert_eq!(Variant::Int32(-3).as_i32(), Some(-3));
+        assert_eq!(Variant::Int64(-3).as_i32(), None);
+
+        assert_eq!(Variant::from("hello").as_str(), Some("hello"));
+        assert_eq!(Variant::Bool(false).as_str(), None);
+
+        let position = Vector3::new(1.0, 2.0, 3.0);
+        assert_eq!(Variant::Vector3(position).as_vector3(), Some(position));
+        assert_eq!(Variant::from("1, 2, 3").as_vector3(), None);
+
+        let referent = Ref::new();
+        assert_eq!(Variant::Ref(referent).as_ref(), Some(referent));
+        assert_eq!(Variant::Int32(0).as_ref(), None);
+    }
+
+    #[test]
+    fn type_names() {
+        assert_eq!(VariantType::CFrame.as_str(), "CFrame");
+        assert_eq!(VariantType::Color3uint8.to_string(), "Color3uint8");
+        assert_eq!("Vector3".parse(), Ok(VariantType::Vector3));
+        assert!("Vector4".parse::<VariantType>().is_err());
+    }
+
+    #[test]
+    fn coerce_colors() {
+        let color = Variant::Color3(Color3::new(1.0, 0.0, 0.5));
+        assert_eq!(
+            color.coerce_color3uint8(),
+            Some(Color3uint8::new(255, 0, 128))
+        );
+        assert_eq!(color.coerce_color3(), Some(Color3::new(1.0, 0.0, 0.5)));
+
+        let color = Variant::Color3uint8(Color3uint8::new(255, 0, 51));
+        assert_eq!(color.coerce_color3(), Some(Color3::new(1.0, 0.0, 0.2)));
+        assert_eq!(
+            color.coerce_color3uint8(),
+            Some(Color3uint8::new(255, 0, 51))
+        );
+
+        assert_eq!(Variant::Bool(true).coerce_color3(), None);
+        assert_eq!(Variant::Bool(true).coerce_color3uint8(), None);
+    }
+
+    #[test]
+    fn bitwise_eq() {
+        let nan = Variant::Float32(std::f32::NAN);
+        assert!(nan.bitwise_eq(&nan.clone()));
+        assert_ne!(nan, nan.clone());
+
+        let zero = Variant::Float64(0.0);
+        let negative_zero = Variant::Float64(-0.0);
+        assert!(!zero.bitwise_eq(&negative_zero));
+        assert_eq!(zero, negative_zero);
+
+        assert!(Variant::Float32(1.5).bitwise_eq(&Variant::Float32(1.5)));
+        assert!(!Variant::Float32(1.5).bitwise_eq(&Variant::Float32(2.5)));
+        assert!(!Variant::Float32(1.5).bitwise_eq(&Variant::Float64(1.5)));
+
+        let nan_vector = Variant::Vector3(Vector3::new(1.0, std::f32::NAN, 3.0));
+        assert!(nan_vector.bitwise_eq(&nan_vector.clone()));
+
+        assert!(Variant::from("hi").bitwise_eq(&Variant::from("hi")));
+        assert!(!Variant::from("hi").bitwise_eq(&Variant::from("bye")));
+
+        let sequence = |value| {
+            Variant::NumberSequence(NumberSequence {
+                keypoints: vec![
+                    NumberSequenceKeypoint::new(0.0, value, 0.0),
+                    NumberSequenceKeypoint::new(1.0, 1.0, 0.0),
+                ],
+            })
+        };
+        assert!(sequence(std::f32::NAN).bitwise_eq(&sequence(std::f32::NAN)));
+        assert!(!sequence(0.0).bitwise_eq(&sequence(-0.0)));
+    }
+
+    #[test]
+    fn bitwise_hash() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash(value: &Variant) -> u64 {
+            let mut state = DefaultHasher::new();
+            value.bitwise_hash(&mut state);
+            state.finish()
+        }
+
+        let values = [
+            Variant::Float32(std::f32::NAN),
+            Variant::Float32(0.0),
+            Variant::Float32(-0.0),
+            Variant::Float64(0.0),
+            Variant::Vector3(Vector3::new(1.0, 2.0, 3.0)),
+            Variant::Color3(Color3::new(1.0, 2.0, 3.0)),
+            Variant::PhysicalProperties(PhysicalProperties::Default),
+            Variant::Content(Content::new()),
+            Variant::Content(Content::from_object(Ref::new())),
+            Variant::Content(Content::from_object(Ref::new())),
+            Variant::from("hi"),
+        ];
+
+        // Values hash the same as themselves and differently from the rest,
+        // matching bitwise_eq.
+        for (i, a) in values.iter().enumerate() {
+            for (j, b) in values.iter().enumerate() {
+                assert_eq!(a.bitwise_eq(b), i == j);
+                assert_eq!(hash(a) == hash(b), i == j);
+            }
+        }
+
+        assert_eq!(hash(&values[0]), hash(&values[0].clone()));
+    }
+}
+
 #[cfg(all(test, feature = "serde"))]
 mod serde_test {
     use super::*;
@@ -44,3 +44,54 @@ impl ConvertVariant for Variant {
         }
     }
 }
+
+/// Tells whether `try_convert` can turn values of type `from` into values of
+/// type `to`, either because they're already the same type or because there's
+/// a conversion between them, like `Int32` to `BrickColor`.
+///
+/// This has to list the same conversions as `try_convert_cow`. The tests below
+/// check that the two agree.
+#[allow(clippy::match_like_matches_macro)]
+pub fn has_conversion(from: VariantType, to: VariantType) -> bool {
+    match (from, to) {
+        (VariantType::Int32, VariantType::BrickColor) => true,
+        (VariantType::Color3, VariantType::Color3uint8) => true,
+        _ => from == to,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::types::{Color3, Vector3};
+
+    #[test]
+    fn has_conversion_matches_try_convert() {
+        let values = vec![
+            Variant::Int32(1),
+            Variant::Color3(Color3::new(1.0, 0.0, 0.0)),
+            Variant::Color3uint8(Color3uint8::new(255, 0, 0)),
+            Variant::BrickColor(BrickColor::White),
+            Variant::Bool(true),
+            Variant::Float32(1.0),
+            Variant::String("Hello".to_owned()),
+            Variant::Vector3(Vector3::new(1.0, 2.0, 3.0)),
+        ];
+        let types: Vec<_> = values.iter().map(Variant::ty).collect();
+
+        for value in &values {
+            for &to in &types {
+                let converted = value.try_convert_ref(to).unwrap();
+
+                assert_eq!(
+                    converted.ty() == to,
+                    has_conversion(value.ty(), to),
+                    "has_conversion disagrees with try_convert for {:?} to {:?}",
+                    value.ty(),
+                    to,
+                );
+            }
+        }
+    }
+}
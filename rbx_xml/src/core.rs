@@ -53,6 +53,18 @@ pub fn find_serialized_property_descriptor(
     find_property_descriptors(class_name, property_name).map(|(_canonical, serialized)| serialized)
 }
 
+/// Finds the name of the item with the given value in the enum with the given
+/// name, like `Plastic` for 256 in `Material`.
+pub fn find_enum_item_name(enum_name: &str, value: u32) -> Option<&'static str> {
+    let enum_descriptor = rbx_reflection_database::get().enums.get(enum_name)?;
+
+    enum_descriptor
+        .items
+        .iter()
+        .find(|(_, &item_value)| item_value == value)
+        .map(|(item_name, _)| item_name.as_ref())
+}
+
 /// Find both the canonical and serialized property descriptors for a given
 /// class and property name pair. These might be the same descriptor!
 fn find_property_descriptors(
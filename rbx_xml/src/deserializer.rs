@@ -1,20 +1,21 @@
 use std::{
+    borrow::Cow,
     collections::{HashMap, HashSet},
     io::Read,
 };
 
 use log::trace;
 use rbx_dom_weak::{
-    types::{Ref, SharedString, Variant, VariantType},
-    InstanceBuilder, WeakDom,
+    types::{Content, Ref, SharedString, Variant, VariantType},
+    InstanceBuilder, RawProperty, WeakDom,
 };
 use rbx_reflection::DataType;
 
 use crate::{
     conversion::ConvertVariant,
-    core::find_canonical_property_descriptor,
+    core::{find_canonical_property_descriptor, find_serialized_property_descriptor},
     error::{DecodeError, DecodeErrorKind},
-    types::read_value_xml,
+    types::{read_value_xml, read_value_xml_as, xml_tag_type},
 };
 
 use crate::deserializer_core::{XmlEventReader, XmlReadEvent};
@@ -34,6 +35,30 @@ pub fn decode_internal<R: Read>(source: R, options: DecodeOptions) -> Result<Wea
     Ok(tree)
 }
 
+pub fn decode_lossy_internal<R: Read>(
+    source: R,
+    options: DecodeOptions,
+) -> (WeakDom, Vec<DecodeError>) {
+    let mut tree = WeakDom::new(InstanceBuilder::new("DataModel"));
+
+    let root_id = tree.root_ref();
+
+    let mut iterator = XmlEventReader::from_source(source);
+    let mut state = ParseState::new(&mut tree, options);
+    state.recovered_errors = Some(Vec::new());
+
+    let result = deserialize_root(&mut iterator, &mut state, root_id);
+    apply_referent_rewrites(&mut state);
+    apply_shared_string_rewrites(&mut state);
+
+    let mut errors = state.recovered_errors.take().unwrap();
+    if let Err(err) = result {
+        errors.push(err);
+    }
+
+    (tree, errors)
+}
+
 /// Describes the strategy that rbx_xml should use when deserializing
 /// properties.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -71,6 +96,10 @@ pub enum DecodePropertyBehavior {
 #[derive(Debug, Clone)]
 pub struct DecodeOptions {
     property_behavior: DecodePropertyBehavior,
+    preserve_unknown_types: bool,
+    error_on_duplicate_property: bool,
+    use_reflected_types: bool,
+    lazy_properties: bool,
 }
 
 impl DecodeOptions {
@@ -79,6 +108,10 @@ impl DecodeOptions {
     pub fn new() -> Self {
         DecodeOptions {
             property_behavior: DecodePropertyBehavior::IgnoreUnknown,
+            preserve_unknown_types: false,
+            error_on_duplicate_property: false,
+            use_reflected_types: false,
+            lazy_properties: false,
         }
     }
 
@@ -86,7 +119,83 @@ impl DecodeOptions {
     /// ones.
     #[inline]
     pub fn property_behavior(self, property_behavior: DecodePropertyBehavior) -> Self {
-        DecodeOptions { property_behavior }
+        DecodeOptions {
+            property_behavior,
+            ..self
+        }
+    }
+
+    /// Determines whether rbx_xml will keep properties with types that it
+    /// doesn't know how to decode. When enabled, these properties are stored
+    /// in the instance's `unknown_properties` as raw XML, and are written back
+    /// out unchanged when the instance is serialized with rbx_xml.
+    ///
+    /// This is off by default, which means these properties are skipped.
+    #[inline]
+    pub fn preserve_unknown_types(self, preserve_unknown_types: bool) -> Self {
+        DecodeOptions {
+            preserve_unknown_types,
+            ..self
+        }
+    }
+
+    /// Determines whether rbx_xml will return an error when an instance has
+    /// the same property more than once, which usually points to a bug in the
+    /// tool that created the file. Properties that have different names in
+    /// the file but the same canonical name, like `size` and `Size`, count as
+    /// the same property.
+    ///
+    /// This is off by default, which means the last value is kept.
+    #[inline]
+    pub fn error_on_duplicate_property(self, error_on_duplicate_property: bool) -> Self {
+        DecodeOptions {
+            error_on_duplicate_property,
+            ..self
+        }
+    }
+
+    /// Determines whether rbx_xml will read properties known by the
+    /// reflection database as the type that the database says they're
+    /// serialized as, instead of the type named by their tag. This helps with
+    /// files from tools that use a more generic tag, like `int` for a packed
+    /// `Color3uint8`.
+    ///
+    /// Properties that the database doesn't know about still use their tag,
+    /// as do types like `Ref` that need more context to be read. Tags that
+    /// name a type which can be converted to the expected one, like a `Color3`
+    /// tag for a `Color3uint8` property, are read normally and converted.
+    ///
+    /// This is off by default.
+    #[inline]
+    pub fn use_reflected_types(self, use_reflected_types: bool) -> Self {
+        DecodeOptions {
+            use_reflected_types,
+            ..self
+        }
+    }
+
+    /// Determines whether rbx_xml will put off decoding property values until
+    /// they're asked for. When enabled, properties are stored in the
+    /// instance's `unknown_properties` as `RawProperty::XmlDeferred` under the
+    /// name they were serialized with, and can be decoded one at a time with
+    /// [`get_property`][crate::get_property]. This saves time when only a few
+    /// properties of each instance are ever read.
+    ///
+    /// `Name` is always decoded, as are `Ref`, `Content`, and `SharedString`
+    /// values, which need information from the rest of the file. Properties
+    /// with unknown types are handled the same way as when this is off.
+    ///
+    /// Undecoded properties are written back out unchanged by rbx_xml, but
+    /// other encoders like rbx_binary skip them, so they should be decoded
+    /// with `get_property` before the tree is saved in another format.
+    ///
+    /// This is off by default.
+    #[inline]
+    pub fn lazy_properties(self, lazy_properties: bool) -> Self {
+        DecodeOptions {
+            lazy_properties,
+            ..self
+        }
     }
 
     /// A utility function to determine whether or not we should reference the
@@ -117,6 +226,8 @@ pub struct ParseState<'a> {
     ///
     /// We need to do that step in two passes because it's possible for
     /// instances to refer to instances that are later in the file.
+    ///
+    /// Keys are passed through `normalize_referent` first.
     referents_to_ids: HashMap<String, Ref>,
 
     /// A list of Ref property rewrites to apply. After the first
@@ -124,6 +235,10 @@ pub struct ParseState<'a> {
     /// correct Ref value by using the referents map.
     referent_rewrites: Vec<ReferentRewrite>,
 
+    /// A list of Content properties that point to instances. These are filled
+    /// in at the same time as Ref properties.
+    content_rewrites: Vec<ReferentRewrite>,
+
     /// A map from shared string hashes (currently MD5, decided by Roblox) to
     /// the actual SharedString type.
     known_shared_strings: HashMap<String, SharedString>,
@@ -136,6 +251,10 @@ pub struct ParseState<'a> {
     /// Contains all of the unknown types that have been found so far. Tracking
     /// them here helps ensure that we only output a warning once per type.
     unknown_type_names: HashSet<String>,
+
+    /// When decoding lossily, contains the errors that caused instances to be
+    /// skipped so far. When `None`, the first error aborts decoding.
+    recovered_errors: Option<Vec<DecodeError>>,
 }
 
 struct ReferentRewrite {
@@ -158,12 +277,61 @@ impl<'a> ParseState<'a> {
             metadata: HashMap::new(),
             referents_to_ids: HashMap::new(),
             referent_rewrites: Vec::new(),
+            content_rewrites: Vec::new(),
             known_shared_strings: HashMap::new(),
             shared_string_rewrites: Vec::new(),
             unknown_type_names: HashSet::new(),
+            recovered_errors: None,
         }
     }
 
+    pub(crate) fn preserve_unknown_types(&self) -> bool {
+        self.options.preserve_unknown_types
+    }
+
+    /// Called when the deserializer encounters a property with an unknown type
+    /// that should be kept in its raw form.
+    pub(crate) fn add_unknown_property(
+        &mut self,
+        id: Ref,
+        property_name: &str,
+        type_name: &str,
+        contents: String,
+    ) {
+        let instance = self.tree.get_by_ref_mut(id).unwrap();
+        instance.unknown_properties.insert(
+            property_name.to_owned(),
+            RawProperty::Xml {
+                type_name: type_name.to_owned(),
+                contents,
+            },
+        );
+    }
+
+    /// Called when the deserializer puts off decoding a property until it's
+    /// asked for.
+    pub(crate) fn add_deferred_property(
+        &mut self,
+        id: Ref,
+        property_name: &str,
+        type_name: &str,
+        contents: String,
+    ) {
+        let instance = self.tree.get_by_ref_mut(id).unwrap();
+        instance.unknown_properties.insert(
+            property_name.to_owned(),
+            RawProperty::XmlDeferred {
+                type_name: type_name.to_owned(),
+                contents,
+            },
+        );
+    }
+
+    pub(crate) fn remove_unknown_property(&mut self, id: Ref, property_name: &str) {
+        let instance = self.tree.get_by_ref_mut(id).unwrap();
+        instance.unknown_properties.remove(property_name);
+    }
+
     /// Called when the deserializer encounters an unknown property type.
     pub fn unknown_type_visited(&mut self, id: Ref, property_name: &str, type_name: &str) {
         if self.unknown_type_names.contains(type_name) {
@@ -194,6 +362,17 @@ impl<'a> ParseState<'a> {
         });
     }
 
+    /// Marks that a Content property on this instance points to another
+    /// instance, and needs to be rewritten once we have a complete view of how
+    /// referents map to Ref values.
+    pub fn add_content_rewrite(&mut self, id: Ref, property_name: String, referent_value: String) {
+        self.content_rewrites.push(ReferentRewrite {
+            id,
+            property_name,
+            referent_value,
+        });
+    }
+
     /// Marks that a property on this instance needs to be rewritten once we
     /// have a complete view of how referents map to Ref values.
     ///
@@ -213,20 +392,60 @@ impl<'a> ParseState<'a> {
 }
 
 fn apply_referent_rewrites(state: &mut ParseState) {
-    for rewrite in &state.referent_rewrites {
-        let new_value = match state.referents_to_ids.get(&rewrite.referent_value) {
+    fn content_object(id: Ref) -> Variant {
+        Variant::Content(Content::from_object(id))
+    }
+
+    let ref_rewrites = state
+        .referent_rewrites
+        .iter()
+        .map(|rewrite| (rewrite, Variant::Ref as fn(Ref) -> Variant));
+    let content_rewrites = state
+        .content_rewrites
+        .iter()
+        .map(|rewrite| (rewrite, content_object as fn(Ref) -> Variant));
+
+    for (rewrite, make_value) in ref_rewrites.chain(content_rewrites) {
+        let referent = normalize_referent(&rewrite.referent_value);
+
+        let new_value = match state.referents_to_ids.get(referent.as_ref()) {
             Some(id) => *id,
             None => continue,
         };
 
-        let instance = state
-            .tree
-            .get_by_ref_mut(rewrite.id)
-            .expect("rbx_xml bug: had ID in referent rewrite list that didn't end up in the tree");
+        // Instances can be missing from the tree if they were skipped while
+        // recovering from an error.
+        if !state.tree.contains(new_value) {
+            continue;
+        }
+
+        let instance = match state.tree.get_by_ref_mut(rewrite.id) {
+            Some(instance) => instance,
+            None => continue,
+        };
 
         instance
             .properties
-            .insert(rewrite.property_name.clone(), Variant::Ref(new_value));
+            .insert(rewrite.property_name.clone(), make_value(new_value));
+    }
+}
+
+/// Roblox writes referents as `RBX` followed by 32 hexadecimal digits, but
+/// files that have been edited by hand or by other tools sometimes leave the
+/// prefix off or change the case of the digits. Referents in either form are
+/// turned into the same key so that they still point to the same instance.
+#[allow(clippy::manual_strip)]
+fn normalize_referent(referent: &str) -> Cow<'_, str> {
+    let digits = if referent.starts_with("RBX") {
+        &referent[3..]
+    } else {
+        referent
+    };
+
+    if digits.len() == 32 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        Cow::Owned(digits.to_ascii_uppercase())
+    } else {
+        Cow::Borrowed(referent)
     }
 }
 
@@ -237,9 +456,10 @@ fn apply_shared_string_rewrites(state: &mut ParseState) {
             None => continue,
         };
 
-        let instance = state.tree.get_by_ref_mut(rewrite.id).expect(
-            "rbx_xml bug: had ID in SharedString rewrite list that didn't end up in the tree",
-        );
+        let instance = match state.tree.get_by_ref_mut(rewrite.id) {
+            Some(instance) => instance,
+            None => continue,
+        };
 
         instance.properties.insert(
             rewrite.property_name.clone(),
@@ -271,7 +491,9 @@ fn deserialize_root<R: Read>(
     let doc_version =
         doc_version.ok_or_else(|| reader.error(DecodeErrorKind::MissingAttribute("version")))?;
 
-    if doc_version != "4" {
+    // Version 3 documents are structurally identical to version 4 ones, so we
+    // accept them too.
+    if doc_version != "4" && doc_version != "3" {
         return Err(reader.error(DecodeErrorKind::WrongDocVersion(doc_version)));
     }
 
@@ -280,11 +502,14 @@ fn deserialize_root<R: Read>(
             XmlReadEvent::StartElement { name, .. } => {
                 match name.local_name.as_str() {
                     "Item" => {
-                        deserialize_instance(reader, state, parent_id)?;
+                        deserialize_child_instance(reader, state, parent_id)?;
                     }
                     "External" => {
-                        // This tag is always meaningless, there's nothing to do
-                        // here except skip it.
+                        // Roblox writes `<External>null</External>` and
+                        // `<External>nil</External>` at the top of files. These
+                        // are left over from an old way of marking referents
+                        // that point outside of the file and never contain
+                        // instances, so we skip them along with their contents.
                         reader.eat_unknown_tag()?;
                     }
                     "Meta" => {
@@ -406,6 +631,42 @@ fn deserialize_shared_string<R: Read>(
     Ok(())
 }
 
+/// Deserializes an `Item` into a new child of the given parent.
+///
+/// When decoding lossily, errors that happen while reading the `Item` are
+/// recorded instead of returned, and the `Item` is skipped along with all of
+/// its descendants. Errors that leave the document unreadable, like malformed
+/// XML, are still returned.
+fn deserialize_child_instance<R: Read>(
+    reader: &mut XmlEventReader<R>,
+    state: &mut ParseState,
+    parent_id: Ref,
+) -> Result<(), DecodeError> {
+    if state.recovered_errors.is_none() {
+        return deserialize_instance(reader, state, parent_id);
+    }
+
+    let depth = reader.depth();
+    let child_count = state.tree.get_by_ref(parent_id).unwrap().children().len();
+
+    if let Err(err) = deserialize_instance(reader, state, parent_id) {
+        let children = state.tree.get_by_ref(parent_id).unwrap().children();
+        if let Some(&partial_id) = children.get(child_count) {
+            state.tree.destroy(partial_id);
+        }
+
+        // If the XML itself is malformed, skipping past the `Item` fails too.
+        // The original error is the one worth reporting, and it ends decoding.
+        if reader.skip_to_depth(depth).is_err() {
+            return Err(err);
+        }
+
+        state.recovered_errors.as_mut().unwrap().push(err);
+    }
+
+    Ok(())
+}
+
 fn deserialize_instance<R: Read>(
     reader: &mut XmlEventReader<R>,
     state: &mut ParseState,
@@ -437,19 +698,27 @@ fn deserialize_instance<R: Read>(
     let instance_id = state.tree.insert(parent_id, builder);
 
     if let Some(referent) = referent {
+        let referent = normalize_referent(&referent).into_owned();
         state.referents_to_ids.insert(referent, instance_id);
     }
 
     let mut properties: HashMap<String, Variant> = HashMap::new();
+    let mut deferred_names: HashMap<String, String> = HashMap::new();
 
     loop {
         match reader.expect_peek()? {
             XmlReadEvent::StartElement { name, .. } => match name.local_name.as_str() {
                 "Properties" => {
-                    deserialize_properties(reader, state, instance_id, &mut properties)?;
+                    deserialize_properties(
+                        reader,
+                        state,
+                        instance_id,
+                        &mut properties,
+                        &mut deferred_names,
+                    )?;
                 }
                 "Item" => {
-                    deserialize_instance(reader, state, instance_id)?;
+                    deserialize_child_instance(reader, state, instance_id)?;
                 }
                 _ => {
                     let event = reader.expect_next().unwrap();
@@ -492,11 +761,32 @@ fn deserialize_instance<R: Read>(
     Ok(())
 }
 
+/// Finds the type that a property is serialized as, if the options ask for
+/// properties to be read as their reflected type.
+fn reflected_type(
+    state: &ParseState,
+    class_name: &str,
+    property_name: &str,
+) -> Option<VariantType> {
+    if !state.options.use_reflected_types {
+        return None;
+    }
+
+    let descriptor = find_serialized_property_descriptor(class_name, property_name)?;
+
+    match &descriptor.data_type {
+        DataType::Value(data_type) => Some(*data_type),
+        DataType::Enum(_enum_name) => Some(VariantType::Enum),
+        _ => None,
+    }
+}
+
 fn deserialize_properties<R: Read>(
     reader: &mut XmlEventReader<R>,
     state: &mut ParseState,
     instance_id: Ref,
     props: &mut HashMap<String, Variant>,
+    deferred_names: &mut HashMap<String, String>,
 ) -> Result<(), DecodeError> {
     reader.expect_start_with_name("Properties")?;
 
@@ -564,13 +854,64 @@ fn deserialize_properties<R: Read>(
             None
         };
 
+        let keep_unknown = state.options.property_behavior == DecodePropertyBehavior::ReadUnknown
+            || state.options.property_behavior == DecodePropertyBehavior::NoReflection;
+
+        if state.options.lazy_properties
+            && can_decode_lazily(&xml_type_name, &xml_property_name)
+            && (maybe_descriptor.is_some() || keep_unknown)
+        {
+            let canonical_name = match maybe_descriptor {
+                Some(descriptor) => descriptor.name.to_string(),
+                None => xml_property_name.clone(),
+            };
+
+            check_duplicate_property(
+                reader,
+                state,
+                &class_name,
+                props,
+                deferred_names,
+                &canonical_name,
+            )?;
+
+            let contents = reader.read_raw_tag_contents()?;
+            state.add_deferred_property(instance_id, &xml_property_name, &xml_type_name, contents);
+
+            // Like with decoded properties, only the last value is kept.
+            props.remove(&canonical_name);
+            if let Some(previous) = deferred_names.insert(canonical_name, xml_property_name.clone())
+            {
+                if previous != xml_property_name {
+                    state.remove_unknown_property(instance_id, &previous);
+                }
+            }
+
+            continue;
+        }
+
         if let Some(descriptor) = maybe_descriptor {
-            let value =
-                match read_value_xml(reader, state, &xml_type_name, instance_id, &descriptor.name)?
-                {
-                    Some(value) => value,
-                    None => continue,
-                };
+            let reflected_ty = reflected_type(state, &class_name, &xml_property_name);
+            let reflected_value = match reflected_ty {
+                Some(ty) => read_value_xml_as(reader, &xml_type_name, ty)?,
+                None => None,
+            };
+
+            let value = match reflected_value {
+                Some(value) => value,
+                None => {
+                    match read_value_xml(
+                        reader,
+                        state,
+                        &xml_type_name,
+                        instance_id,
+                        &descriptor.name,
+                    )? {
+                        Some(value) => value,
+                        None => continue,
+                    }
+                }
+            };
 
             let xml_ty = value.ty();
 
@@ -583,9 +924,13 @@ fn deserialize_properties<R: Read>(
             // For example:
             // - Int/Float widening from 32-bit to 64-bit
             // - BrickColor properties turning into Color3
-            let expected_type = match &descriptor.data_type {
-                DataType::Value(data_type) => *data_type,
-                DataType::Enum(_enum_name) => VariantType::Enum,
+            //
+            // When reading properties as their reflected type, the value is
+            // converted to the serialized type instead.
+            let expected_type = match (reflected_ty, &descriptor.data_type) {
+                (Some(ty), _) => ty,
+                (None, DataType::Value(data_type)) => *data_type,
+                (None, DataType::Enum(_enum_name)) => VariantType::Enum,
                 _ => unimplemented!(),
             };
 
@@ -607,7 +952,15 @@ fn deserialize_properties<R: Read>(
                 }
             };
 
-            props.insert(descriptor.name.to_string(), value);
+            insert_property(
+                reader,
+                state,
+                instance_id,
+                props,
+                deferred_names,
+                descriptor.name.to_string(),
+                value,
+            )?;
         } else {
             match state.options.property_behavior {
                 DecodePropertyBehavior::IgnoreUnknown => {
@@ -636,7 +989,15 @@ fn deserialize_properties<R: Read>(
                         Some(value) => value,
                         None => continue,
                     };
-                    props.insert(xml_property_name, value);
+                    insert_property(
+                        reader,
+                        state,
+                        instance_id,
+                        props,
+                        deferred_names,
+                        xml_property_name,
+                        value,
+                    )?;
                 }
                 DecodePropertyBehavior::ErrorOnUnknown => {
                     return Err(reader.error(DecodeErrorKind::UnknownProperty {
@@ -648,3 +1009,496 @@ fn deserialize_properties<R: Read>(
         }
     }
 }
+
+/// Tells whether a property can be kept as raw XML and decoded later. This is
+/// only done for simple types, since values like `Ref` point to other parts of
+/// the file, and unknown types are handled by `preserve_unknown_types`.
+fn can_decode_lazily(xml_type_name: &str, xml_property_name: &str) -> bool {
+    xml_property_name != "Name" && xml_tag_type(xml_type_name).is_some()
+}
+
+/// Returns an error if the options ask for one and the instance already has a
+/// property with the given canonical name, whether or not it's been decoded.
+fn check_duplicate_property<R: Read>(
+    reader: &XmlEventReader<R>,
+    state: &ParseState,
+    class_name: &str,
+    props: &HashMap<String, Variant>,
+    deferred_names: &HashMap<String, String>,
+    property_name: &str,
+) -> Result<(), DecodeError> {
+    if state.options.error_on_duplicate_property
+        && (props.contains_key(property_name) || deferred_names.contains_key(property_name))
+    {
+        return Err(reader.error(DecodeErrorKind::DuplicateProperty {
+            class_name: class_name.to_owned(),
+            property_name: property_name.to_owned(),
+        }));
+    }
+
+    Ok(())
+}
+
+fn insert_property<R: Read>(
+    reader: &XmlEventReader<R>,
+    state: &mut ParseState,
+    instance_id: Ref,
+    props: &mut HashMap<String, Variant>,
+    deferred_names: &mut HashMap<String, String>,
+    property_name: String,
+    value: Variant,
+) -> Result<(), DecodeError> {
+    let class_name = &state.tree.get_by_ref(instance_id).unwrap().class;
+    check_duplicate_property(
+        reader,
+        state,
+        class_name,
+        props,
+        deferred_names,
+        &property_name,
+    )?;
+
+    // A value that was put off earlier is replaced by this one.
+    if let Some(deferred_name) = deferred_names.remove(&property_name) {
+        state.remove_unknown_property(instance_id, &deferred_name);
+    }
+
+    props.insert(property_name, value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::types::{Color3uint8, Enum, Vector3};
+
+    #[test]
+    fn serialized_names_become_canonical() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <Color3uint8 name="Color3uint8">16711808</Color3uint8>
+                        <Vector3 name="size">
+                            <X>1</X>
+                            <Y>2</Y>
+                            <Z>3</Z>
+                        </Vector3>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let tree = decode_internal(document.as_bytes(), DecodeOptions::default()).unwrap();
+        let part = tree.get_by_ref(tree.root().children()[0]).unwrap();
+
+        assert_eq!(
+            part.properties.get("Color"),
+            Some(&Variant::Color3uint8(Color3uint8::new(255, 0, 128)))
+        );
+        assert_eq!(
+            part.properties.get("Size"),
+            Some(&Variant::Vector3(Vector3::new(1.0, 2.0, 3.0)))
+        );
+
+        assert!(!part.properties.contains_key("Color3uint8"));
+        assert!(!part.properties.contains_key("size"));
+    }
+
+    #[test]
+    fn lossy_skips_bad_item() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Folder" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Good</string>
+                    </Properties>
+                </Item>
+                <Item class="IntValue" referent="RBX2">
+                    <Properties>
+                        <string name="Name">Bad</string>
+                        <int name="Value">not a number</int>
+                    </Properties>
+                    <Item class="Folder" referent="RBX3">
+                        <Properties>
+                            <string name="Name">BadChild</string>
+                        </Properties>
+                    </Item>
+                </Item>
+                <Item class="ObjectValue" referent="RBX4">
+                    <Properties>
+                        <string name="Name">AlsoGood</string>
+                        <Ref name="Value">RBX1</Ref>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        assert!(decode_internal(document.as_bytes(), DecodeOptions::default()).is_err());
+
+        let (tree, errors) = decode_lossy_internal(document.as_bytes(), DecodeOptions::default());
+        assert_eq!(errors.len(), 1);
+
+        let children: Vec<_> = tree
+            .root()
+            .children()
+            .iter()
+            .map(|&referent| tree.get_by_ref(referent).unwrap())
+            .collect();
+
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].name, "Good");
+        assert_eq!(children[1].name, "AlsoGood");
+        assert_eq!(
+            children[1].properties.get("Value"),
+            Some(&Variant::Ref(children[0].referent()))
+        );
+    }
+
+    #[test]
+    fn mixed_referent_formats() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Folder" referent="RBX0123456789ABCDEF0123456789ABCDEF">
+                    <Properties>
+                        <string name="Name">Target</string>
+                    </Properties>
+                </Item>
+                <Item class="ObjectValue" referent="1">
+                    <Properties>
+                        <string name="Name">Bare</string>
+                        <Ref name="Value">0123456789abcdef0123456789abcdef</Ref>
+                    </Properties>
+                </Item>
+                <Item class="ObjectValue" referent="2">
+                    <Properties>
+                        <string name="Name">Prefixed</string>
+                        <Ref name="Value">RBX0123456789ABCDEF0123456789ABCDEF</Ref>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let tree = decode_internal(document.as_bytes(), DecodeOptions::default()).unwrap();
+        let children: Vec<_> = tree
+            .root()
+            .children()
+            .iter()
+            .map(|&referent| tree.get_by_ref(referent).unwrap())
+            .collect();
+
+        let target = Variant::Ref(children[0].referent());
+        assert_eq!(children[1].properties.get("Value"), Some(&target));
+        assert_eq!(children[2].properties.get("Value"), Some(&target));
+    }
+
+    #[test]
+    fn lossy_malformed_xml() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Folder" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Good</string>
+                    </Properties>
+                </Item>
+                <Item class="Folder"
+        "#;
+
+        let (tree, errors) = decode_lossy_internal(document.as_bytes(), DecodeOptions::default());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(tree.root().children().len(), 1);
+    }
+
+    #[test]
+    fn lossy_malformed_xml_in_item() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Folder" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Good</string>
+                    </Properties>
+                </Item>
+                <Item class="Folder" referent="RBX2">
+                    <Item class="BoolValue" referent="RBX3">
+                        <Properties>
+                            <bool name="Value">true</boo>
+                        </Properties>
+                    </Item>
+                </Item>
+            </roblox>
+        "#;
+
+        let (tree, errors) = decode_lossy_internal(document.as_bytes(), DecodeOptions::default());
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(!errors[0].to_string().contains("end-of-file"));
+        assert_eq!(tree.root().children().len(), 1);
+    }
+
+    #[test]
+    fn version_3() {
+        let document = r#"
+            <roblox version="3">
+                <Item class="StringValue" referent="RBX1">
+                    <Properties>
+                        <string name="Value">Hello</string>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let tree = decode_internal(document.as_bytes(), DecodeOptions::default()).unwrap();
+        let value = tree.get_by_ref(tree.root().children()[0]).unwrap();
+
+        assert_eq!(value.properties.get("Value"), Some(&Variant::from("Hello")));
+    }
+
+    #[test]
+    fn unknown_version() {
+        let document = r#"<roblox version="5"></roblox>"#;
+
+        assert!(decode_internal(document.as_bytes(), DecodeOptions::default()).is_err());
+    }
+
+    #[test]
+    fn use_reflected_types() {
+        // Part.Color is serialized as a Color3uint8, which some tools write as
+        // a plain packed integer.
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <int name="Color3uint8">16711680</int>
+                        <int name="NotARealProperty">5</int>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        // By default, the tag decides the type and the value ends up as an
+        // integer, even though the property is known.
+        let tree = crate::from_str_default(document).unwrap();
+        let part = tree.get_by_ref(tree.root().children()[0]).unwrap();
+        assert_eq!(
+            part.properties.get("Color"),
+            Some(&Variant::Int32(16711680))
+        );
+
+        let options = DecodeOptions::new()
+            .use_reflected_types(true)
+            .property_behavior(DecodePropertyBehavior::ReadUnknown);
+        let tree = crate::from_str(document, options).unwrap();
+        let part = tree.get_by_ref(tree.root().children()[0]).unwrap();
+
+        assert_eq!(
+            part.properties.get("Color"),
+            Some(&Variant::Color3uint8(Color3uint8::new(255, 0, 0)))
+        );
+        assert_eq!(
+            part.properties.get("NotARealProperty"),
+            Some(&Variant::Int32(5))
+        );
+    }
+
+    #[test]
+    fn use_reflected_types_with_convertible_tag() {
+        // A Color3 tag can't be read as a packed Color3uint8, but it can be
+        // converted to one after it's read.
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <Color3 name="Color3uint8">
+                            <R>1</R>
+                            <G>0</G>
+                            <B>0</B>
+                        </Color3>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = DecodeOptions::new().use_reflected_types(true);
+        let tree = crate::from_str(document, options).unwrap();
+        let part = tree.get_by_ref(tree.root().children()[0]).unwrap();
+
+        assert_eq!(
+            part.properties.get("Color"),
+            Some(&Variant::Color3uint8(Color3uint8::new(255, 0, 0)))
+        );
+    }
+
+    #[test]
+    fn duplicate_property() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <bool name="Anchored">true</bool>
+                        <bool name="Anchored">false</bool>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let tree = crate::from_str_default(document).unwrap();
+        let part = tree.get_by_ref(tree.root().children()[0]).unwrap();
+        assert_eq!(part.properties.get("Anchored"), Some(&Variant::Bool(false)));
+
+        let options = DecodeOptions::new().error_on_duplicate_property(true);
+        let error = crate::from_str(document, options).unwrap_err();
+        assert!(error.to_string().contains("Part.Anchored"));
+    }
+
+    #[test]
+    fn comments_and_processing_instructions() {
+        let document = r#"
+            <roblox version="4">
+                <?tool generated-by="something"?>
+                <Item class="Part" referent="RBX1">
+                    <!-- Properties are written by a tool -->
+                    <Properties>
+                        <bool name="Anchored">true</bool>
+                        <!-- A comment between properties -->
+                        <?tool note="and a processing instruction"?>
+                        <float name="Transparency">0.<!-- inside a value -->5</float>
+                    </Properties>
+                    <!-- A comment after properties -->
+                </Item>
+            </roblox>
+        "#;
+
+        let tree = crate::from_str_default(document).unwrap();
+        let part = tree.get_by_ref(tree.root().children()[0]).unwrap();
+
+        assert_eq!(part.properties.get("Anchored"), Some(&Variant::Bool(true)));
+        assert_eq!(
+            part.properties.get("Transparency"),
+            Some(&Variant::Float32(0.5))
+        );
+    }
+
+    #[test]
+    fn empty_tags() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="TextLabel" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Label</string>
+                        <string name="Text"></string>
+                        <string name="Font"/>
+                        <Content name="Image"></Content>
+                        <Content name="Texture"/>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = DecodeOptions::new().property_behavior(DecodePropertyBehavior::NoReflection);
+        let tree = crate::from_str(document, options).unwrap();
+        let label = tree.get_by_ref(tree.root().children()[0]).unwrap();
+
+        assert_eq!(label.properties.get("Text"), Some(&Variant::from("")));
+        assert_eq!(label.properties.get("Font"), Some(&Variant::from("")));
+        assert_eq!(
+            label.properties.get("Image"),
+            Some(&Variant::Content(Content::new()))
+        );
+        assert_eq!(
+            label.properties.get("Texture"),
+            Some(&Variant::Content(Content::new()))
+        );
+
+        // There's no sensible empty number, so those are still errors.
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <float name="Transparency"/>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        assert!(crate::from_str_default(document).is_err());
+    }
+
+    #[test]
+    fn external_is_skipped() {
+        let document = r#"
+            <roblox version="4">
+                <External>null</External>
+                <External>nil</External>
+                <Item class="ObjectValue" referent="RBX1">
+                    <Properties>
+                        <Ref name="Value">null</Ref>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let tree = crate::from_str_default(document).unwrap();
+        assert_eq!(tree.root().children().len(), 1);
+
+        let value = tree.get_by_ref(tree.root().children()[0]).unwrap();
+        assert_eq!(value.class, "ObjectValue");
+        assert_eq!(
+            value.properties.get("Value"),
+            Some(&Variant::Ref(Ref::none()))
+        );
+    }
+
+    #[test]
+    fn token_becomes_enum() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <token name="Material">272</token>
+                        <token name="CustomEnum">3</token>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = DecodeOptions::new().property_behavior(DecodePropertyBehavior::ReadUnknown);
+        let tree = crate::from_str(document, options).unwrap();
+        let part = tree.get_by_ref(tree.root().children()[0]).unwrap();
+
+        assert_eq!(
+            part.properties.get("Material"),
+            Some(&Variant::Enum(Enum::from_u32(272)))
+        );
+        assert_eq!(
+            part.properties.get("CustomEnum"),
+            Some(&Variant::Enum(Enum::from_u32(3)))
+        );
+    }
+
+    #[test]
+    fn from_path() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Folder" referent="RBX1">
+                    <Properties>
+                        <string name="Name">OnDisk</string>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let path =
+            std::env::temp_dir().join(format!("rbx_xml-from-path-{}.rbxmx", std::process::id()));
+        std::fs::write(&path, document).unwrap();
+
+        let decoded = crate::from_path_default(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let decoded = decoded.unwrap();
+        let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+        assert_eq!(folder.name, "OnDisk");
+
+        let error = crate::from_path_default(&path).unwrap_err();
+        assert_eq!(error.line(), 1);
+    }
+}
@@ -1,7 +1,11 @@
 use std::io::Read;
 
 use log::trace;
-use xml::{attribute::OwnedAttribute, reader::ParserConfig};
+use xml::{
+    attribute::OwnedAttribute,
+    escape::{escape_str_attribute, escape_str_pcdata},
+    reader::ParserConfig,
+};
 
 use crate::{
     core::XmlType,
@@ -17,16 +21,38 @@ pub struct XmlEventReader<R: Read> {
     reader: xml::EventReader<R>,
     peeked: Option<Result<XmlReadEvent, xml::reader::Error>>,
     finished: bool,
+
+    /// The number of elements that have been opened but not yet closed, not
+    /// counting a peeked event.
+    depth: usize,
 }
 
 impl<R: Read> Iterator for XmlEventReader<R> {
     type Item = XmlReadResult;
 
     fn next(&mut self) -> Option<XmlReadResult> {
-        if let Some(value) = self.peeked.take() {
-            return Some(value);
+        let event = match self.peeked.take() {
+            Some(value) => Some(value),
+            None => self.read_event(),
+        };
+
+        match &event {
+            Some(Ok(XmlReadEvent::StartElement { .. })) => self.depth += 1,
+            Some(Ok(XmlReadEvent::EndElement { .. })) => self.depth = self.depth.saturating_sub(1),
+            _ => {}
         }
 
+        event
+    }
+}
+
+impl<R: Read> XmlEventReader<R> {
+    /// Reads the next event from xml-rs, skipping over whitespace and
+    /// processing instructions. Comments are skipped by xml-rs itself.
+    ///
+    /// Files written by other tools can contain these anywhere, so skipping
+    /// them here means that none of the code reading events has to.
+    fn read_event(&mut self) -> Option<XmlReadResult> {
         if self.finished {
             return None;
         }
@@ -34,7 +60,9 @@ impl<R: Read> Iterator for XmlEventReader<R> {
         loop {
             match self.reader.next() {
                 Ok(item) => match item {
-                    XmlReadEvent::Whitespace(_) => continue,
+                    XmlReadEvent::Whitespace(_) | XmlReadEvent::ProcessingInstruction { .. } => {
+                        continue
+                    }
                     XmlReadEvent::EndDocument => {
                         self.finished = true;
                         return Some(Ok(item));
@@ -48,9 +76,7 @@ impl<R: Read> Iterator for XmlEventReader<R> {
             }
         }
     }
-}
 
-impl<R: Read> XmlEventReader<R> {
     /// Constructs a new `XmlEventReader` from a source that implements `Read`.
     pub fn from_source(source: R) -> XmlEventReader<R> {
         let reader = ParserConfig::new()
@@ -61,6 +87,7 @@ impl<R: Read> XmlEventReader<R> {
             reader,
             peeked: None,
             finished: false,
+            depth: 0,
         }
     }
 
@@ -70,10 +97,26 @@ impl<R: Read> XmlEventReader<R> {
             return self.peeked.as_ref();
         }
 
-        self.peeked = self.next();
+        self.peeked = self.read_event();
         self.peeked.as_ref()
     }
 
+    /// Returns the number of elements that have been opened but not yet
+    /// closed.
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Consumes events until every element deeper than `depth` has been
+    /// closed.
+    pub(crate) fn skip_to_depth(&mut self, depth: usize) -> Result<(), NewDecodeError> {
+        while self.depth > depth {
+            self.expect_next()?;
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn error<T: Into<DecodeErrorKind>>(&self, kind: T) -> NewDecodeError {
         NewDecodeError::new_from_reader(kind.into(), &self.reader)
     }
@@ -272,6 +315,60 @@ impl<R: Read> XmlEventReader<R> {
         Ok(value)
     }
 
+    /// Consumes the next tag and returns everything inside of it as a string
+    /// of XML, which can be written back out with
+    /// `XmlEventWriter::write_raw_property`.
+    pub(crate) fn read_raw_tag_contents(&mut self) -> Result<String, NewDecodeError> {
+        match self.expect_next()? {
+            XmlReadEvent::StartElement { .. } => {}
+            other => return Err(self.error(DecodeErrorKind::UnexpectedXmlEvent(other))),
+        }
+
+        let mut contents = String::new();
+        let mut depth = 0;
+
+        loop {
+            match self.expect_next()? {
+                XmlReadEvent::StartElement {
+                    name, attributes, ..
+                } => {
+                    depth += 1;
+                    contents.push('<');
+                    contents.push_str(&name.borrow().to_repr());
+
+                    for attribute in attributes {
+                        contents.push(' ');
+                        contents.push_str(&attribute.name.borrow().to_repr());
+                        contents.push_str("=\"");
+                        contents.push_str(&escape_str_attribute(&attribute.value));
+                        contents.push('"');
+                    }
+
+                    contents.push('>');
+                }
+                XmlReadEvent::EndElement { name } => {
+                    if depth == 0 {
+                        break;
+                    }
+
+                    depth -= 1;
+                    contents.push_str("</");
+                    contents.push_str(&name.borrow().to_repr());
+                    contents.push('>');
+                }
+                XmlReadEvent::Characters(text) => contents.push_str(&escape_str_pcdata(&text)),
+                XmlReadEvent::CData(text) => {
+                    contents.push_str("<![CDATA[");
+                    contents.push_str(&text);
+                    contents.push_str("]]>");
+                }
+                _ => {}
+            }
+        }
+
+        Ok(contents)
+    }
+
     /// Consume events from the iterator until we reach the end of the next tag.
     pub fn eat_unknown_tag(&mut self) -> Result<(), NewDecodeError> {
         let mut depth = 0;
@@ -32,6 +32,19 @@ impl DecodeError {
         }
     }
 
+    /// Creates an error for an I/O failure that happened before any of the
+    /// document could be read, like failing to open a file. These errors are
+    /// reported at the start of the document.
+    pub(crate) fn new_from_io(error: io::Error) -> DecodeError {
+        DecodeError {
+            inner: Box::new(DecodeErrorImpl {
+                kind: DecodeErrorKind::Io(error),
+                line: 1,
+                column: 1,
+            }),
+        }
+    }
+
     /// 1-based line number in the document where the error occured.
     pub fn line(&self) -> usize {
         self.inner.line
@@ -69,6 +82,7 @@ struct DecodeErrorImpl {
 #[derive(Debug)]
 pub(crate) enum DecodeErrorKind {
     // Errors from other crates
+    Io(io::Error),
     Xml(xml::reader::Error),
     ParseFloat(std::num::ParseFloatError),
     ParseInt(std::num::ParseIntError),
@@ -84,7 +98,15 @@ pub(crate) enum DecodeErrorKind {
         property_name: String,
     },
     InvalidContent(&'static str),
+    IntegerOutOfRange {
+        value: i64,
+        type_name: &'static str,
+    },
     NameMustBeString(VariantType),
+    DuplicateProperty {
+        class_name: String,
+        property_name: String,
+    },
     UnsupportedPropertyConversion {
         class_name: String,
         property_name: String,
@@ -99,13 +121,18 @@ impl fmt::Display for DecodeErrorKind {
         use self::DecodeErrorKind::*;
 
         match self {
+            Io(err) => write!(output, "{}", err),
             Xml(err) => write!(output, "{}", err),
             ParseFloat(err) => write!(output, "{}", err),
             ParseInt(err) => write!(output, "{}", err),
             DecodeBase64(err) => write!(output, "{}", err),
 
             WrongDocVersion(version) => {
-                write!(output, "Invalid version '{}', expected version 4", version)
+                write!(
+                    output,
+                    "Invalid version '{}', expected version 3 or 4",
+                    version
+                )
             }
             UnexpectedEof => write!(output, "Unexpected end-of-file"),
             UnexpectedXmlEvent(event) => write!(output, "Unexpected XML event {:?}", event),
@@ -121,11 +148,24 @@ impl fmt::Display for DecodeErrorKind {
                 class_name, property_name
             ),
             InvalidContent(explain) => write!(output, "Invalid text content: {}", explain),
+            IntegerOutOfRange { value, type_name } => write!(
+                output,
+                "Value {} is out of range for type {}",
+                value, type_name
+            ),
             NameMustBeString(ty) => write!(
                 output,
                 "The 'Name' property must be of type String, but it was {:?}",
                 ty
             ),
+            DuplicateProperty {
+                class_name,
+                property_name,
+            } => write!(
+                output,
+                "Property {}.{} was specified more than once",
+                class_name, property_name
+            ),
             UnsupportedPropertyConversion {
                 class_name,
                 property_name,
@@ -147,6 +187,7 @@ impl std::error::Error for DecodeErrorKind {
         use self::DecodeErrorKind::*;
 
         match self {
+            Io(err) => Some(err),
             Xml(err) => Some(err),
             ParseFloat(err) => Some(err),
             ParseInt(err) => Some(err),
@@ -158,7 +199,9 @@ impl std::error::Error for DecodeErrorKind {
             | MissingAttribute(_)
             | UnknownProperty { .. }
             | InvalidContent(_)
+            | IntegerOutOfRange { .. }
             | NameMustBeString(_)
+            | DuplicateProperty { .. }
             | UnsupportedPropertyConversion { .. } => None,
         }
     }
@@ -227,7 +270,10 @@ pub(crate) enum EncodeErrorKind {
         class_name: String,
         property_name: String,
     },
-    UnsupportedPropertyType(VariantType),
+    UnsupportedPropertyType {
+        property_name: String,
+        ty: VariantType,
+    },
     UnsupportedPropertyConversion {
         class_name: String,
         property_name: String,
@@ -235,6 +281,13 @@ pub(crate) enum EncodeErrorKind {
         actual_type: VariantType,
         message: String,
     },
+    InvalidRawProperty {
+        property_name: String,
+        source: xml::reader::Error,
+    },
+    EmptyName {
+        class_name: String,
+    },
 }
 
 impl fmt::Display for EncodeErrorKind {
@@ -253,9 +306,11 @@ impl fmt::Display for EncodeErrorKind {
                 "Property {}.{} is unknown",
                 class_name, property_name
             ),
-            UnsupportedPropertyType(ty) => {
-                write!(output, "Properties of type {:?} cannot be encoded yet", ty)
-            }
+            UnsupportedPropertyType { property_name, ty } => write!(
+                output,
+                "Property {} is of type {:?}, which cannot be encoded yet",
+                property_name, ty
+            ),
             UnsupportedPropertyConversion {
                 class_name,
                 property_name,
@@ -268,6 +323,21 @@ impl fmt::Display for EncodeErrorKind {
                  When trying to convert the value, this error occured: {}",
                 class_name, property_name, expected_type, actual_type, message
             ),
+            InvalidRawProperty {
+                property_name,
+                source,
+            } => write!(
+                output,
+                "Unknown property {} does not contain valid XML: {}",
+                property_name, source
+            ),
+            EmptyName { class_name } => {
+                write!(
+                    output,
+                    "An instance of class {} has an empty name",
+                    class_name
+                )
+            }
         }
     }
 }
@@ -279,10 +349,12 @@ impl std::error::Error for EncodeErrorKind {
         match self {
             Io(err) => Some(err),
             Xml(err) => Some(err),
+            InvalidRawProperty { source, .. } => Some(source),
 
             UnknownProperty { .. }
-            | UnsupportedPropertyType(_)
-            | UnsupportedPropertyConversion { .. } => None,
+            | UnsupportedPropertyType { .. }
+            | UnsupportedPropertyConversion { .. }
+            | EmptyName { .. } => None,
         }
     }
 }
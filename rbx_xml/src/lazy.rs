@@ -0,0 +1,322 @@
+use rbx_dom_weak::{types::Variant, Instance, RawProperty};
+use rbx_reflection::{PropertyKind, PropertySerialization, ReflectionDatabase};
+
+use crate::{deserializer::decode_internal, error::DecodeError, DecodeOptions};
+
+#[cfg(test)]
+thread_local! {
+    /// The number of properties decoded by `get_property`, so that tests can
+    /// check that properties aren't decoded before they're asked for.
+    static LAZY_DECODE_COUNT: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// Returns the value of the property with the given name, decoding it first if
+/// it was left undecoded by [`DecodeOptions::lazy_properties`].
+///
+/// Decoded values are moved from the instance's `unknown_properties` into its
+/// `properties`, so each property is only decoded once. `options` should be the
+/// options that the tree was decoded with, so that the value is read the same
+/// way that it would have been up front. The reflection database is used to
+/// find the name that the property was serialized under, like `size` for
+/// `Part.Size`.
+///
+/// Properties with types that rbx_xml doesn't know how to decode are never
+/// returned, even if they were kept with
+/// [`DecodeOptions::preserve_unknown_types`].
+pub fn get_property<'a>(
+    instance: &'a mut Instance,
+    property_name: &str,
+    options: &DecodeOptions,
+) -> Result<Option<&'a Variant>, DecodeError> {
+    if instance.properties.contains_key(property_name) {
+        return Ok(instance.properties.get(property_name));
+    }
+
+    let raw_name = if instance.unknown_properties.contains_key(property_name) {
+        property_name
+    } else if options.use_reflection() {
+        let database = rbx_reflection_database::get();
+
+        match serialized_name(database, &instance.class, property_name) {
+            Some(name) if instance.unknown_properties.contains_key(name) => name,
+            _ => return Ok(None),
+        }
+    } else {
+        return Ok(None);
+    };
+
+    let (decoded_name, value) = match &instance.unknown_properties[raw_name] {
+        RawProperty::XmlDeferred {
+            type_name,
+            contents,
+        } => match decode_raw_property(&instance.class, raw_name, type_name, contents, options)? {
+            Some(decoded) => decoded,
+            None => return Ok(None),
+        },
+
+        _ => return Ok(None),
+    };
+
+    instance.unknown_properties.remove(raw_name);
+
+    Ok(Some(
+        instance.properties.entry(decoded_name).or_insert(value),
+    ))
+}
+
+/// Decodes a single raw property by wrapping it in a document of its own, so
+/// that it goes through the same conversions as properties that were decoded
+/// up front.
+fn decode_raw_property(
+    class_name: &str,
+    property_name: &str,
+    type_name: &str,
+    contents: &str,
+    options: &DecodeOptions,
+) -> Result<Option<(String, Variant)>, DecodeError> {
+    #[cfg(test)]
+    LAZY_DECODE_COUNT.with(|count| count.set(count.get() + 1));
+
+    let document = format!(
+        r#"<roblox version="4"><Item class="{class}"><Properties><string name="Name"></string><{ty} name="{name}">{contents}</{ty}></Properties></Item></roblox>"#,
+        class = escape_attribute(class_name),
+        ty = type_name,
+        name = escape_attribute(property_name),
+        contents = contents,
+    );
+
+    let options = options.clone().lazy_properties(false);
+    let mut tree = decode_internal(document.as_bytes(), options)?;
+
+    let decoded_ref = tree.root().children()[0];
+    let decoded = tree.get_by_ref_mut(decoded_ref).unwrap();
+    let properties = std::mem::take(&mut decoded.properties);
+
+    Ok(properties.into_iter().next())
+}
+
+/// Finds the name that a property is serialized under, following aliases and
+/// superclasses.
+fn serialized_name<'db>(
+    database: &'db ReflectionDatabase,
+    class_name: &str,
+    property_name: &str,
+) -> Option<&'db str> {
+    let mut class = database.classes.get(class_name)?;
+
+    loop {
+        if let Some(property) = class.properties.get(property_name) {
+            return match &property.kind {
+                PropertyKind::Canonical {
+                    serialization: PropertySerialization::SerializesAs(name),
+                } => Some(name),
+                PropertyKind::Alias { alias_for } => {
+                    serialized_name(database, &class.name, alias_for)
+                }
+                _ => Some(&property.name),
+            };
+        }
+
+        class = database.classes.get(class.superclass.as_deref()?)?;
+    }
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::types::{Color3uint8, Vector3};
+
+    fn decode_count() -> usize {
+        LAZY_DECODE_COUNT.with(|count| count.get())
+    }
+
+    #[test]
+    fn lazy_properties() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Lazy</string>
+                        <Vector3 name="size">
+                            <X>4</X>
+                            <Y>1</Y>
+                            <Z>2</Z>
+                        </Vector3>
+                        <Color3uint8 name="Color3uint8">4294901760</Color3uint8>
+                        <bool name="Anchored">true</bool>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = DecodeOptions::new().lazy_properties(true);
+        let mut tree = crate::from_str(document, options.clone()).unwrap();
+
+        let part_ref = tree.root().children()[0];
+        let part = tree.get_by_ref_mut(part_ref).unwrap();
+
+        assert_eq!(part.name, "Lazy");
+        assert!(part.properties.is_empty());
+        assert_eq!(part.unknown_properties.len(), 3);
+        assert_eq!(
+            part.unknown_properties.get("Anchored"),
+            Some(&RawProperty::XmlDeferred {
+                type_name: "bool".to_owned(),
+                contents: "true".to_owned(),
+            })
+        );
+
+        let before = decode_count();
+
+        assert_eq!(
+            get_property(part, "Size", &options).unwrap(),
+            Some(&Variant::Vector3(Vector3::new(4.0, 1.0, 2.0)))
+        );
+        assert_eq!(decode_count(), before + 1);
+
+        // The decoded value is kept, so asking again doesn't decode it again.
+        assert_eq!(
+            get_property(part, "Size", &options).unwrap(),
+            Some(&Variant::Vector3(Vector3::new(4.0, 1.0, 2.0)))
+        );
+        assert_eq!(decode_count(), before + 1);
+
+        assert!(!part.unknown_properties.contains_key("size"));
+        assert!(part.unknown_properties.contains_key("Color3uint8"));
+        assert!(part.unknown_properties.contains_key("Anchored"));
+        assert_eq!(part.properties.len(), 1);
+
+        assert_eq!(
+            get_property(part, "Color", &options).unwrap(),
+            Some(&Variant::Color3uint8(Color3uint8::new(255, 0, 0)))
+        );
+        assert_eq!(get_property(part, "Missing", &options).unwrap(), None);
+        assert_eq!(decode_count(), before + 2);
+    }
+
+    #[test]
+    fn lazy_unknown_types() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Folder" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Lazy</string>
+                        <FancyType name="Fancy"><X>1</X></FancyType>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = DecodeOptions::new()
+            .lazy_properties(true)
+            .preserve_unknown_types(true);
+        let mut tree = crate::from_str(document, options.clone()).unwrap();
+
+        let folder_ref = tree.root().children()[0];
+        let folder = tree.get_by_ref_mut(folder_ref).unwrap();
+
+        // Properties with unknown types are kept the same way that they are
+        // without lazy_properties, and can't be decoded later.
+        assert_eq!(
+            folder.unknown_properties.get("Fancy"),
+            Some(&RawProperty::Xml {
+                type_name: "FancyType".to_owned(),
+                contents: "<X>1</X>".to_owned(),
+            })
+        );
+        assert_eq!(get_property(folder, "Fancy", &options).unwrap(), None);
+        assert!(folder.unknown_properties.contains_key("Fancy"));
+    }
+
+    #[test]
+    fn lazy_reflected_types() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Lazy</string>
+                        <int name="Color3uint8">16711680</int>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = DecodeOptions::new()
+            .lazy_properties(true)
+            .use_reflected_types(true);
+        let mut tree = crate::from_str(document, options.clone()).unwrap();
+
+        let part_ref = tree.root().children()[0];
+        let part = tree.get_by_ref_mut(part_ref).unwrap();
+
+        assert_eq!(
+            get_property(part, "Color", &options).unwrap(),
+            Some(&Variant::Color3uint8(Color3uint8::new(255, 0, 0)))
+        );
+    }
+
+    #[test]
+    fn lazy_duplicate_properties() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Lazy</string>
+                        <Vector3 name="size"><X>1</X><Y>1</Y><Z>1</Z></Vector3>
+                        <Vector3 name="Size"><X>2</X><Y>2</Y><Z>2</Z></Vector3>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = DecodeOptions::new().lazy_properties(true);
+        let mut tree = crate::from_str(document, options.clone()).unwrap();
+
+        // Without error_on_duplicate_property, the last value is kept.
+        let part_ref = tree.root().children()[0];
+        let part = tree.get_by_ref_mut(part_ref).unwrap();
+
+        assert_eq!(part.unknown_properties.len(), 1);
+        assert_eq!(
+            get_property(part, "Size", &options).unwrap(),
+            Some(&Variant::Vector3(Vector3::new(2.0, 2.0, 2.0)))
+        );
+
+        let options = options.error_on_duplicate_property(true);
+        assert!(crate::from_str(document, options).is_err());
+    }
+
+    #[test]
+    fn lazy_round_trip() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="RBX1">
+                    <Properties>
+                        <string name="Name">Lazy</string>
+                        <bool name="Anchored">true</bool>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = DecodeOptions::new().lazy_properties(true);
+        let tree = crate::from_str(document, options).unwrap();
+        let part_ref = tree.root().children()[0];
+
+        // Properties that were never decoded are written back out unchanged.
+        let encoded = crate::to_string_default(&tree, &[part_ref]).unwrap();
+        let decoded = crate::from_str_default(&encoded).unwrap();
+        let part = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+
+        assert_eq!(part.name, "Lazy");
+        assert_eq!(part.properties.get("Anchored"), Some(&Variant::Bool(true)));
+    }
+}
@@ -65,7 +65,13 @@
 //!
 //! ## Serialization
 //! To serialize an existing `WeakDom` instance, use methods like
-//! [`to_writer_default`][to_writer_default] or [`to_writer`][to_writer].
+//! [`to_writer_default`][to_writer_default] or [`to_writer`][to_writer]. If you
+//! want the result as a string instead, use [`to_string_default`] or
+//! [`to_string`].
+//!
+//! All of these methods accept a list of referents to serialize, which lets
+//! you serialize only part of a tree, like a user's selection. Each referent
+//! is written along with all of its descendants.
 //!
 //! For example, to re-save the place file we loaded above:
 //!
@@ -110,6 +116,7 @@ mod core;
 mod deserializer;
 mod deserializer_core;
 mod error;
+mod lazy;
 mod serializer;
 mod serializer_core;
 mod types;
@@ -117,16 +124,27 @@ mod types;
 #[cfg(test)]
 mod test_util;
 
-use std::io::{Read, Write};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Write},
+    path::Path,
+};
 
 use rbx_dom_weak::{types::Ref, WeakDom};
 
-use crate::{deserializer::decode_internal, serializer::encode_internal};
+use crate::{
+    deserializer::{decode_internal, decode_lossy_internal},
+    serializer::encode_internal,
+};
 
 pub use crate::{
     deserializer::{DecodeOptions, DecodePropertyBehavior},
     error::{DecodeError, EncodeError},
-    serializer::{EncodeOptions, EncodePropertyBehavior},
+    lazy::get_property,
+    serializer::{
+        Color3Format, EmptyNameBehavior, EncodeOptions, EncodePropertyBehavior,
+        PhysicalPropertiesFormat, ReferentStyle,
+    },
 };
 
 /// Decodes an XML-format model or place from something that implements the
@@ -141,6 +159,35 @@ pub fn from_reader_default<R: Read>(reader: R) -> Result<WeakDom, DecodeError> {
     decode_internal(reader, DecodeOptions::default())
 }
 
+/// Decodes an XML-format model or place from something that implements the
+/// `std::io::Read` trait, skipping over any `Item` that can't be decoded
+/// instead of failing.
+///
+/// Returns every instance that could be decoded along with the errors that
+/// were encountered. This is useful for salvaging what's left of a damaged
+/// file. Decoding still stops early if the document isn't valid XML.
+pub fn from_reader_lossy<R: Read>(
+    reader: R,
+    options: DecodeOptions,
+) -> (WeakDom, Vec<DecodeError>) {
+    decode_lossy_internal(reader, options)
+}
+
+/// Decodes an XML-format model or place from the file at the given path,
+/// reading it through a buffer.
+///
+/// Failing to open the file is reported as a `DecodeError`.
+pub fn from_path<P: AsRef<Path>>(path: P, options: DecodeOptions) -> Result<WeakDom, DecodeError> {
+    let file = File::open(path).map_err(DecodeError::new_from_io)?;
+    decode_internal(BufReader::new(file), options)
+}
+
+/// Decodes an XML-format model or place from the file at the given path using
+/// the default decoder options.
+pub fn from_path_default<P: AsRef<Path>>(path: P) -> Result<WeakDom, DecodeError> {
+    from_path(path, DecodeOptions::default())
+}
+
 /// Decodes an XML-format model or place from a string.
 pub fn from_str<S: AsRef<str>>(reader: S, options: DecodeOptions) -> Result<WeakDom, DecodeError> {
     decode_internal(reader.as_ref().as_bytes(), options)
@@ -154,6 +201,11 @@ pub fn from_str_default<S: AsRef<str>>(reader: S) -> Result<WeakDom, DecodeError
 
 /// Serializes a subset of the given tree to an XML format model or place,
 /// writing to something that implements the `std::io::Write` trait.
+///
+/// The document is written out as the tree is walked instead of being built
+/// up in memory first, so this is the best choice for large places. Writers
+/// like `File` should be wrapped in a `BufWriter`, since many small writes
+/// are made.
 pub fn to_writer<W: Write>(
     writer: W,
     tree: &WeakDom,
@@ -173,3 +225,22 @@ pub fn to_writer_default<W: Write>(
 ) -> Result<(), EncodeError> {
     encode_internal(writer, tree, ids, EncodeOptions::default())
 }
+
+/// Serializes a subset of the given tree to an XML format model or place,
+/// returning it as a string.
+pub fn to_string(
+    tree: &WeakDom,
+    ids: &[Ref],
+    options: EncodeOptions,
+) -> Result<String, EncodeError> {
+    let mut buffer = Vec::new();
+    encode_internal(&mut buffer, tree, ids, options)?;
+
+    Ok(String::from_utf8(buffer).expect("rbx_xml should always produce valid UTF-8"))
+}
+
+/// Serializes a subset of the given tree to an XML format model or place,
+/// returning it as a string using the default encoder options.
+pub fn to_string_default(tree: &WeakDom, ids: &[Ref]) -> Result<String, EncodeError> {
+    to_string(tree, ids, EncodeOptions::default())
+}
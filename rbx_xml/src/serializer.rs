@@ -1,17 +1,26 @@
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, HashMap},
+    fmt,
     io::Write,
+    sync::Arc,
 };
 
 use rbx_dom_weak::{
-    types::{Ref, SharedString, SharedStringHash, Variant, VariantType},
-    WeakDom,
+    types::{
+        Color3, Color3uint8, PhysicalProperties, Ref, SharedString, SharedStringHash, Variant,
+        VariantType,
+    },
+    RawProperty, WeakDom,
 };
 use rbx_reflection::DataType;
 
 use crate::{
     conversion::ConvertVariant,
-    core::find_serialized_property_descriptor,
+    core::{
+        find_canonical_property_descriptor, find_enum_item_name,
+        find_serialized_property_descriptor,
+    },
     error::{EncodeError as NewEncodeError, EncodeErrorKind},
     types::write_value_xml,
 };
@@ -71,10 +80,91 @@ pub enum EncodePropertyBehavior {
     NoReflection,
 }
 
+/// Describes how rbx_xml should format the referents of the instances it
+/// writes. Any of these styles can be read back by rbx_xml and Roblox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ReferentStyle {
+    /// Numbers instances in the order that they're written, starting at 0.
+    ///
+    /// This is the default.
+    Sequential,
+
+    /// Writes the instance's `Ref` as 32 uppercase hexadecimal digits, like
+    /// `0123456789ABCDEF0123456789ABCDEF`. Referents stay the same between
+    /// saves of the same tree.
+    Hex,
+
+    /// Writes `RBX` followed by the instance's `Ref` as 32 uppercase
+    /// hexadecimal digits, like Roblox Studio does.
+    RbxPrefixed,
+}
+
+/// Describes how rbx_xml should write `Color3` and `Color3uint8` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Color3Format {
+    /// Writes colors as the type that the reflection database expects, like
+    /// Roblox Studio does. Unknown properties are written as-is.
+    ///
+    /// This is the default.
+    Auto,
+
+    /// Writes every color as a `Color3` with floating point components.
+    Float,
+
+    /// Writes every color as a packed `Color3uint8`.
+    Uint8,
+}
+
+/// Describes how rbx_xml should write `PhysicalProperties` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PhysicalPropertiesFormat {
+    /// Writes a `<PhysicalProperties>` tag containing a `CustomPhysics` flag
+    /// and the custom values, like Roblox Studio does today.
+    ///
+    /// This is the default.
+    Modern,
+
+    /// Writes the `Friction` and `Elasticity` of custom physical properties as
+    /// separate `<float>` properties, which is how parts stored them before
+    /// `PhysicalProperties` existed. Other values are dropped, and nothing is
+    /// written for parts using their material's defaults.
+    Legacy,
+}
+
+/// Describes what rbx_xml should do when it writes an instance with an empty
+/// name. Roblox Studio behaves confusingly with instances like these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EmptyNameBehavior {
+    /// Writes the empty name as-is.
+    ///
+    /// This is the default.
+    Keep,
+
+    /// Writes the instance's class name as its name instead, like Roblox
+    /// does for new instances.
+    UseClassName,
+
+    /// Returns an error.
+    Error,
+}
+
+type PropertyFilter = dyn Fn(&str, &str, &Variant) -> bool + Send + Sync;
+
 /// Options available for serializing an XML-format model or place.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct EncodeOptions {
     property_behavior: EncodePropertyBehavior,
+    enum_name_comments: bool,
+    property_filter: Option<Arc<PropertyFilter>>,
+    respect_archivable: bool,
+    referent_style: ReferentStyle,
+    empty_name_behavior: EmptyNameBehavior,
+    color3_format: Color3Format,
+    physical_properties_format: PhysicalPropertiesFormat,
 }
 
 impl EncodeOptions {
@@ -83,6 +173,13 @@ impl EncodeOptions {
     pub fn new() -> Self {
         EncodeOptions {
             property_behavior: EncodePropertyBehavior::IgnoreUnknown,
+            enum_name_comments: false,
+            property_filter: None,
+            respect_archivable: false,
+            referent_style: ReferentStyle::Sequential,
+            empty_name_behavior: EmptyNameBehavior::Keep,
+            color3_format: Color3Format::Auto,
+            physical_properties_format: PhysicalPropertiesFormat::Modern,
         }
     }
 
@@ -90,7 +187,101 @@ impl EncodeOptions {
     /// ones.
     #[inline]
     pub fn property_behavior(self, property_behavior: EncodePropertyBehavior) -> Self {
-        EncodeOptions { property_behavior }
+        EncodeOptions {
+            property_behavior,
+            ..self
+        }
+    }
+
+    /// Determines whether rbx_xml will write a comment containing the name of
+    /// the enum item after each enum property, like `<!-- Plastic -->` for a
+    /// `Material` of 256. The value itself is still written as a number.
+    ///
+    /// This makes files easier to read and diff. It's off by default and has
+    /// no effect when reflection is turned off.
+    #[inline]
+    pub fn enum_name_comments(self, enum_name_comments: bool) -> Self {
+        EncodeOptions {
+            enum_name_comments,
+            ..self
+        }
+    }
+
+    /// Sets a function that decides which properties rbx_xml will serialize.
+    /// It's called with the class name of the instance, the name of the
+    /// property, and its value. Properties that it returns `false` for are
+    /// skipped.
+    ///
+    /// The property name is the one stored in the tree, before any conversion
+    /// to its serialized name. `Name` is always written.
+    #[inline]
+    pub fn property_filter<F>(self, property_filter: F) -> Self
+    where
+        F: Fn(&str, &str, &Variant) -> bool + Send + Sync + 'static,
+    {
+        EncodeOptions {
+            property_filter: Some(Arc::new(property_filter)),
+            ..self
+        }
+    }
+
+    /// Determines whether rbx_xml will leave out instances whose `Archivable`
+    /// property is `false`, along with all of their descendants. This matches
+    /// how Roblox Studio saves files. It's off by default.
+    #[inline]
+    pub fn respect_archivable(self, respect_archivable: bool) -> Self {
+        EncodeOptions {
+            respect_archivable,
+            ..self
+        }
+    }
+
+    /// Determines how rbx_xml will format the referents of the instances it
+    /// writes. Matching the style that a tool or version of Roblox Studio
+    /// writes can cut down on noise when diffing files.
+    #[inline]
+    pub fn referent_style(self, referent_style: ReferentStyle) -> Self {
+        EncodeOptions {
+            referent_style,
+            ..self
+        }
+    }
+
+    /// Determines what rbx_xml will do with instances that have an empty
+    /// name. `WeakDom::validate_names` can be used to find these instances
+    /// ahead of time.
+    #[inline]
+    pub fn empty_name_behavior(self, empty_name_behavior: EmptyNameBehavior) -> Self {
+        EncodeOptions {
+            empty_name_behavior,
+            ..self
+        }
+    }
+
+    /// Determines how rbx_xml will write colors. The default matches what
+    /// Roblox Studio writes, and rbx_xml can read colors written in any of
+    /// these formats. The others are meant for tools that only understand one
+    /// of them, and it isn't known whether Roblox accepts every property in a
+    /// format that it doesn't write itself.
+    #[inline]
+    pub fn color3_format(self, color3_format: Color3Format) -> Self {
+        EncodeOptions {
+            color3_format,
+            ..self
+        }
+    }
+
+    /// Determines how rbx_xml will write physical properties. The legacy form
+    /// is only useful for producing files for very old versions of Roblox.
+    #[inline]
+    pub fn physical_properties_format(
+        self,
+        physical_properties_format: PhysicalPropertiesFormat,
+    ) -> Self {
+        EncodeOptions {
+            physical_properties_format,
+            ..self
+        }
     }
 
     pub(crate) fn use_reflection(&self) -> bool {
@@ -98,6 +289,28 @@ impl EncodeOptions {
     }
 }
 
+impl fmt::Debug for EncodeOptions {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("EncodeOptions")
+            .field("property_behavior", &self.property_behavior)
+            .field("enum_name_comments", &self.enum_name_comments)
+            .field("respect_archivable", &self.respect_archivable)
+            .field("referent_style", &self.referent_style)
+            .field("empty_name_behavior", &self.empty_name_behavior)
+            .field("color3_format", &self.color3_format)
+            .field(
+                "physical_properties_format",
+                &self.physical_properties_format,
+            )
+            .field(
+                "property_filter",
+                &self.property_filter.as_ref().map(|_| "<function>"),
+            )
+            .finish()
+    }
+}
+
 impl Default for EncodeOptions {
     fn default() -> EncodeOptions {
         EncodeOptions::new()
@@ -109,7 +322,7 @@ pub struct EmitState {
 
     /// A map of IDs written so far to the generated referent that they use.
     /// This map is used to correctly emit Ref properties.
-    referent_map: HashMap<Ref, u32>,
+    referent_map: HashMap<Ref, String>,
 
     /// The referent value that will be used for emitting the next instance.
     next_referent: u32,
@@ -129,16 +342,22 @@ impl EmitState {
         }
     }
 
-    pub fn map_id(&mut self, id: Ref) -> u32 {
-        match self.referent_map.get(&id) {
-            Some(&value) => value,
-            None => {
-                let referent = self.next_referent;
-                self.referent_map.insert(id, referent);
+    pub fn map_id(&mut self, id: Ref) -> String {
+        if let Some(referent) = self.referent_map.get(&id) {
+            return referent.clone();
+        }
+
+        let referent = match self.options.referent_style {
+            ReferentStyle::Sequential => {
                 self.next_referent += 1;
-                referent
+                (self.next_referent - 1).to_string()
             }
-        }
+            ReferentStyle::Hex => id.to_string().to_uppercase(),
+            ReferentStyle::RbxPrefixed => format!("RBX{}", id.to_string().to_uppercase()),
+        };
+
+        self.referent_map.insert(id, referent.clone());
+        referent
     }
 
     pub fn add_shared_string(&mut self, value: SharedString) {
@@ -158,22 +377,38 @@ fn serialize_instance<'a, W: Write>(
     property_buffer: &mut Vec<(&'a String, &'a Variant)>,
 ) -> Result<(), NewEncodeError> {
     let instance = tree.get_by_ref(id).unwrap();
+
+    if state.options.respect_archivable
+        && instance.properties.get("Archivable") == Some(&Variant::Bool(false))
+    {
+        return Ok(());
+    }
+
+    let name = if instance.name.is_empty() {
+        match state.options.empty_name_behavior {
+            EmptyNameBehavior::Keep => &instance.name,
+            EmptyNameBehavior::UseClassName => &instance.class,
+            EmptyNameBehavior::Error => {
+                return Err(writer.error(EncodeErrorKind::EmptyName {
+                    class_name: instance.class.clone(),
+                }));
+            }
+        }
+    } else {
+        &instance.name
+    };
+
     let mapped_id = state.map_id(id);
 
     writer.write(
         XmlWriteEvent::start_element("Item")
             .attr("class", &instance.class)
-            .attr("referent", &mapped_id.to_string()),
+            .attr("referent", &mapped_id),
     )?;
 
     writer.write(XmlWriteEvent::start_element("Properties"))?;
 
-    write_value_xml(
-        writer,
-        state,
-        "Name",
-        &Variant::String(instance.name.clone()),
-    )?;
+    write_value_xml(writer, state, "Name", &Variant::String(name.clone()))?;
 
     // Move references to our properties into property_buffer so we can sort
     // them and iterate them in order.
@@ -181,6 +416,19 @@ fn serialize_instance<'a, W: Write>(
     property_buffer.sort_unstable_by_key(|(key, _)| *key);
 
     for (property_name, value) in property_buffer.drain(..) {
+        // Name was already written from `instance.name`, which is where the
+        // deserializer puts it. Writing it again would produce a duplicate
+        // property that Roblox may read instead of the real name.
+        if property_name == "Name" {
+            continue;
+        }
+
+        if let Some(property_filter) = &state.options.property_filter {
+            if !property_filter(&instance.class, property_name, value) {
+                continue;
+            }
+        }
+
         let maybe_serialized_descriptor = if state.options.use_reflection() {
             find_serialized_property_descriptor(&instance.class, property_name)
         } else {
@@ -191,7 +439,12 @@ fn serialize_instance<'a, W: Write>(
             let data_type = match &serialized_descriptor.data_type {
                 DataType::Value(data_type) => *data_type,
                 DataType::Enum(_enum_name) => VariantType::Enum,
-                _ => unimplemented!(),
+                _ => {
+                    return Err(writer.error(EncodeErrorKind::UnsupportedPropertyType {
+                        property_name: property_name.clone(),
+                        ty: value.ty(),
+                    }))
+                }
             };
 
             let converted_value = match value.try_convert_ref(data_type) {
@@ -209,15 +462,30 @@ fn serialize_instance<'a, W: Write>(
                 }
             };
 
-            write_value_xml(writer, state, &serialized_descriptor.name, &converted_value)?;
+            let converted_value = apply_color3_format(converted_value, state.options.color3_format);
+
+            write_property(writer, state, &serialized_descriptor.name, &converted_value)?;
+
+            if state.options.enum_name_comments {
+                if let (DataType::Enum(enum_name), Variant::Enum(value)) =
+                    (&serialized_descriptor.data_type, converted_value.as_ref())
+                {
+                    if let Some(item_name) = find_enum_item_name(enum_name, value.to_u32()) {
+                        writer.write(XmlWriteEvent::comment(item_name))?;
+                    }
+                }
+            }
         } else {
             match state.options.property_behavior {
                 EncodePropertyBehavior::IgnoreUnknown => {}
                 EncodePropertyBehavior::WriteUnknown | EncodePropertyBehavior::NoReflection => {
                     // We'll take this value as-is with no conversions on
-                    // either the name or value.
+                    // either the name or value, other than the color format
+                    // that was asked for.
 
-                    write_value_xml(writer, state, property_name, value)?;
+                    let value =
+                        apply_color3_format(Cow::Borrowed(value), state.options.color3_format);
+                    write_property(writer, state, property_name, &value)?;
                 }
                 EncodePropertyBehavior::ErrorOnUnknown => {
                     return Err(writer.error(EncodeErrorKind::UnknownProperty {
@@ -229,6 +497,44 @@ fn serialize_instance<'a, W: Write>(
         }
     }
 
+    // Properties that weren't decoded are written back exactly as they were
+    // read, unless they've since been replaced by a decoded value.
+    let mut unknown_properties: Vec<_> = instance
+        .unknown_properties
+        .iter()
+        .filter(|(property_name, _)| !instance.properties.contains_key(*property_name))
+        .collect();
+    unknown_properties.sort_unstable_by_key(|(property_name, _)| *property_name);
+
+    for (property_name, raw_property) in unknown_properties {
+        match raw_property {
+            RawProperty::Xml {
+                type_name,
+                contents,
+            } => {
+                writer.write_raw_property(type_name, property_name, contents)?;
+            }
+
+            // Deferred properties are stored under the name they were
+            // serialized with, which might not be their canonical name.
+            RawProperty::XmlDeferred {
+                type_name,
+                contents,
+            } => {
+                let replaced = find_canonical_property_descriptor(&instance.class, property_name)
+                    .map_or(false, |descriptor| {
+                        instance.properties.contains_key(descriptor.name.as_ref())
+                    });
+
+                if !replaced {
+                    writer.write_raw_property(type_name, property_name, contents)?;
+                }
+            }
+
+            _ => {}
+        }
+    }
+
     writer.write(XmlWriteEvent::end_element())?;
 
     for child_id in instance.children() {
@@ -240,6 +546,39 @@ fn serialize_instance<'a, W: Write>(
     Ok(())
 }
 
+/// Writes a single property, taking options that change how values are laid
+/// out into account.
+fn write_property<W: Write>(
+    writer: &mut XmlEventWriter<W>,
+    state: &mut EmitState,
+    property_name: &str,
+    value: &Variant,
+) -> Result<(), NewEncodeError> {
+    match (state.options.physical_properties_format, value) {
+        (PhysicalPropertiesFormat::Legacy, Variant::PhysicalProperties(properties)) => {
+            if let PhysicalProperties::Custom(custom) = properties {
+                write_value_xml(writer, state, "Elasticity", &custom.elasticity.into())?;
+                write_value_xml(writer, state, "Friction", &custom.friction.into())?;
+            }
+
+            Ok(())
+        }
+        _ => write_value_xml(writer, state, property_name, value),
+    }
+}
+
+fn apply_color3_format(value: Cow<'_, Variant>, format: Color3Format) -> Cow<'_, Variant> {
+    match (format, value.as_ref()) {
+        (Color3Format::Float, Variant::Color3uint8(color)) => {
+            Cow::Owned(Variant::Color3(Color3::from(*color)))
+        }
+        (Color3Format::Uint8, Variant::Color3(color)) => {
+            Cow::Owned(Variant::Color3uint8(Color3uint8::from(*color)))
+        }
+        _ => value,
+    }
+}
+
 fn serialize_shared_strings<W: Write>(
     writer: &mut XmlEventWriter<W>,
     state: &mut EmitState,
@@ -268,3 +607,491 @@ fn serialize_shared_strings<W: Write>(
     writer.end_element()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::{
+        types::{Color3, Color3uint8, CustomPhysicalProperties, Enum, Region3, Vector3},
+        InstanceBuilder,
+    };
+
+    use crate::DecodeOptions;
+
+    #[test]
+    fn enum_name_comments() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Part").with_property("Material", Enum::from_u32(256)),
+        );
+
+        let mut encoded = Vec::new();
+        let options = EncodeOptions::new().enum_name_comments(true);
+        encode_internal(&mut encoded, &dom, &[dom.root_ref()], options).unwrap();
+
+        let encoded = String::from_utf8(encoded).unwrap();
+        assert!(encoded.contains(r#"<token name="Material">256</token>"#));
+        assert!(encoded.contains("<!-- Plastic -->"));
+
+        let decoded = crate::from_str_default(&encoded).unwrap();
+        let part = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+
+        assert_eq!(
+            part.properties.get("Material"),
+            Some(&Variant::Enum(Enum::from_u32(256)))
+        );
+    }
+
+    #[test]
+    fn unknown_enum_value() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Part" referent="part">
+                    <Properties>
+                        <token name="Material">4000000000</token>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let decoded = crate::from_str_default(document).unwrap();
+        let part = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+        assert_eq!(
+            part.properties.get("Material"),
+            Some(&Variant::Enum(Enum::from_u32(4000000000)))
+        );
+
+        let mut encoded = Vec::new();
+        let options = EncodeOptions::new().enum_name_comments(true);
+        encode_internal(&mut encoded, &decoded, decoded.root().children(), options).unwrap();
+
+        let encoded = String::from_utf8(encoded).unwrap();
+        assert!(encoded.contains(r#"<token name="Material">4000000000</token>"#));
+        assert!(!encoded.contains("<!--"));
+    }
+
+    #[test]
+    fn referent_styles() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("ObjectValue"));
+        let root_ref = dom.root_ref();
+        dom.root_mut().set_property("Value", root_ref);
+
+        let hex = root_ref.to_string().to_uppercase();
+        let expected = [
+            (ReferentStyle::Sequential, "0".to_owned()),
+            (ReferentStyle::Hex, hex.clone()),
+            (ReferentStyle::RbxPrefixed, format!("RBX{}", hex)),
+        ];
+
+        for (style, referent) in expected.iter() {
+            let options = EncodeOptions::new().referent_style(*style);
+            let encoded = crate::to_string(&dom, &[root_ref], options).unwrap();
+
+            assert!(encoded.contains(&format!(r#"referent="{}""#, referent)));
+            assert!(encoded.contains(&format!(r#"<Ref name="Value">{}</Ref>"#, referent)));
+
+            let decoded = crate::from_str_default(&encoded).unwrap();
+            let value = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+            assert_eq!(
+                value.properties.get("Value"),
+                Some(&Variant::Ref(value.referent()))
+            );
+        }
+    }
+
+    #[test]
+    fn preserve_unknown_types() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Folder" referent="0">
+                    <Properties>
+                        <string name="Name">Folder</string>
+                        <FancyNewType name="Fancy">
+                            <X a="1 &amp; 2">3 &lt; 4</X>
+                            <Y><![CDATA[  padded  ]]></Y>
+                        </FancyNewType>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = DecodeOptions::new().preserve_unknown_types(true);
+        let decoded = crate::from_str(document, options.clone()).unwrap();
+        let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+
+        let raw_property = RawProperty::Xml {
+            type_name: "FancyNewType".to_owned(),
+            contents: r#"<X a="1 &amp; 2">3 &lt; 4</X><Y><![CDATA[  padded  ]]></Y>"#.to_owned(),
+        };
+        assert_eq!(folder.unknown_properties.get("Fancy"), Some(&raw_property));
+
+        let encoded = crate::to_string_default(&decoded, decoded.root().children()).unwrap();
+        let decoded_again = crate::from_str(&encoded, options).unwrap();
+        let folder = decoded_again
+            .get_by_ref(decoded_again.root().children()[0])
+            .unwrap();
+        assert_eq!(folder.unknown_properties.get("Fancy"), Some(&raw_property));
+
+        let decoded = crate::from_str_default(document).unwrap();
+        let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+        assert!(folder.unknown_properties.is_empty());
+    }
+
+    #[test]
+    fn streams_to_writer() {
+        /// Counts the bytes written to it without keeping any of them.
+        #[derive(Default)]
+        struct CountingWriter {
+            total: usize,
+            largest_write: usize,
+        }
+
+        impl Write for CountingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.total += buf.len();
+                self.largest_write = self.largest_write.max(buf.len());
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("Folder"));
+        for i in 0..100 {
+            dom.insert(
+                dom.root_ref(),
+                InstanceBuilder::new("StringValue")
+                    .with_name(format!("Value{}", i))
+                    .with_property("Value", "Some text to write out"),
+            );
+        }
+
+        let mut writer = CountingWriter::default();
+        crate::to_writer_default(&mut writer, &dom, &[dom.root_ref()]).unwrap();
+
+        let encoded = crate::to_string_default(&dom, &[dom.root_ref()]).unwrap();
+        assert_eq!(writer.total, encoded.len());
+
+        // If the document were built up in memory first, it would be written
+        // all at once.
+        assert!(writer.largest_write < encoded.len() / 10);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let mut dom = WeakDom::new(InstanceBuilder::new("DataModel"));
+        let workspace_ref = dom.insert(dom.root_ref(), InstanceBuilder::new("Workspace"));
+        let part_ref = dom.insert(
+            workspace_ref,
+            InstanceBuilder::new("Part").with_name("Baseplate"),
+        );
+        let storage_ref = dom.insert(dom.root_ref(), InstanceBuilder::new("ReplicatedStorage"));
+        let folder_ref = dom.insert(
+            storage_ref,
+            InstanceBuilder::new("Folder").with_name("Assets"),
+        );
+
+        let encoded = crate::to_string_default(&dom, &[part_ref, folder_ref]).unwrap();
+        let decoded = crate::from_str_default(&encoded).unwrap();
+
+        let roots: Vec<_> = decoded
+            .root()
+            .children()
+            .iter()
+            .map(|&referent| decoded.get_by_ref(referent).unwrap())
+            .collect();
+
+        assert_eq!(roots.len(), 2);
+        assert_eq!(
+            (roots[0].class.as_str(), roots[0].name.as_str()),
+            ("Part", "Baseplate")
+        );
+        assert_eq!(
+            (roots[1].class.as_str(), roots[1].name.as_str()),
+            ("Folder", "Assets")
+        );
+    }
+
+    #[test]
+    fn bare_named_folder() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_name("Assets")
+                .with_property("Name", "Stale"),
+        );
+
+        let encoded = crate::to_string_default(&dom, &[dom.root_ref()]).unwrap();
+        assert_eq!(encoded.matches(r#"<string name="Name">"#).count(), 1);
+        assert!(encoded.contains(r#"<string name="Name">Assets</string>"#));
+
+        let decoded = crate::from_str_default(&encoded).unwrap();
+        let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+
+        assert_eq!(folder.name, "Assets");
+        assert!(folder.properties.is_empty());
+    }
+
+    #[test]
+    fn empty_name_behavior() {
+        let dom = WeakDom::new(InstanceBuilder::new("Folder").with_name(""));
+
+        let encoded = crate::to_string_default(&dom, &[dom.root_ref()]).unwrap();
+        assert!(encoded.contains(r#"<string name="Name"></string>"#));
+
+        let options = EncodeOptions::new().empty_name_behavior(EmptyNameBehavior::UseClassName);
+        let encoded = crate::to_string(&dom, &[dom.root_ref()], options).unwrap();
+        assert!(encoded.contains(r#"<string name="Name">Folder</string>"#));
+
+        let options = EncodeOptions::new().empty_name_behavior(EmptyNameBehavior::Error);
+        let error = crate::to_string(&dom, &[dom.root_ref()], options).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "An instance of class Folder has an empty name"
+        );
+    }
+
+    #[test]
+    fn unsupported_type() {
+        let dom = WeakDom::new(InstanceBuilder::new("Folder").with_property(
+            "Region",
+            Region3::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 1.0)),
+        ));
+
+        let options = EncodeOptions::new().property_behavior(EncodePropertyBehavior::WriteUnknown);
+        let result = encode_internal(Vec::new(), &dom, &[dom.root_ref()], options);
+
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("Region"));
+        assert!(error.contains("Region3"));
+    }
+
+    #[test]
+    fn stable_output() {
+        fn encode(properties: &[(&str, f32)]) -> Vec<u8> {
+            let part = InstanceBuilder::new("Part").with_properties(properties.iter().copied());
+            let dom = WeakDom::new(
+                InstanceBuilder::new("Model")
+                    .with_child(part)
+                    .with_child(InstanceBuilder::new("Folder")),
+            );
+
+            let mut encoded = Vec::new();
+            encode_internal(&mut encoded, &dom, &[dom.root_ref()], EncodeOptions::new()).unwrap();
+            encoded
+        }
+
+        let properties = [
+            ("Transparency", 0.5),
+            ("Reflectance", 0.25),
+            ("Elasticity", 0.75),
+            ("Friction", 0.1),
+        ];
+        let mut reversed = properties;
+        reversed.reverse();
+
+        let first = encode(&properties);
+        assert_eq!(first, encode(&properties));
+        assert_eq!(first, encode(&reversed));
+    }
+
+    #[test]
+    fn serialize_selection() {
+        let selected = InstanceBuilder::new("Folder").with_name("Selected");
+        let selected_ref = selected.referent();
+
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Model")
+                .with_name("Whole")
+                .with_child(selected.with_child(InstanceBuilder::new("Part").with_name("Inner")))
+                .with_child(InstanceBuilder::new("Folder").with_name("Unselected")),
+        );
+
+        let whole = crate::to_string_default(&dom, &[dom.root_ref()]).unwrap();
+        for name in &["Whole", "Selected", "Inner", "Unselected"] {
+            assert!(whole.contains(name));
+        }
+
+        let selection = crate::to_string_default(&dom, &[selected_ref]).unwrap();
+        assert!(selection.contains("Selected"));
+        assert!(selection.contains("Inner"));
+        assert!(!selection.contains("Whole"));
+        assert!(!selection.contains("Unselected"));
+
+        let decoded = crate::from_str_default(&selection).unwrap();
+        assert_eq!(decoded.root().children().len(), 1);
+    }
+
+    #[test]
+    fn part_color_as_color3uint8() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Part").with_property("Color", Color3::new(1.0, 0.0, 0.5)),
+        );
+
+        let encoded = crate::to_string_default(&dom, &[dom.root_ref()]).unwrap();
+
+        // Studio stores BasePart.Color as a packed Color3uint8 named
+        // Color3uint8, even when it's given a float Color3.
+        assert!(encoded.contains(r#"<Color3uint8 name="Color3uint8">16711808</Color3uint8>"#));
+        assert!(!encoded.contains("<Color3 "));
+    }
+
+    #[test]
+    fn color3_format() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Part")
+                .with_property("Color", Color3::new(1.0, 0.0, 0.0))
+                .with_child(
+                    InstanceBuilder::new("Color3Value")
+                        .with_property("Value", Color3::new(1.0, 0.0, 0.0)),
+                ),
+        );
+
+        let encode = |format| {
+            let options = EncodeOptions::new().color3_format(format);
+            crate::to_string(&dom, &[dom.root_ref()], options).unwrap()
+        };
+
+        let float = r#"<R>1</R>"#;
+        let part_uint8 = r#"<Color3uint8 name="Color3uint8">16711680</Color3uint8>"#;
+        let value_uint8 = r#"<Color3uint8 name="Value">16711680</Color3uint8>"#;
+
+        let auto = encode(Color3Format::Auto);
+        assert!(auto.contains(part_uint8));
+        assert!(auto.contains(r#"<Color3 name="Value">"#));
+
+        let float_only = encode(Color3Format::Float);
+        assert!(float_only.contains(r#"<Color3 name="Color3uint8">"#));
+        assert!(float_only.contains(r#"<Color3 name="Value">"#));
+        assert_eq!(float_only.matches(float).count(), 2);
+        assert!(!float_only.contains("<Color3uint8 "));
+
+        let uint8_only = encode(Color3Format::Uint8);
+        assert!(uint8_only.contains(part_uint8));
+        assert!(uint8_only.contains(value_uint8));
+        assert!(!uint8_only.contains("<Color3 "));
+
+        // Whichever type a color was written as, it reads back as the same
+        // color.
+        let as_uint8 = |value: Option<&Variant>| match value {
+            Some(Variant::Color3(color)) => Some(Color3uint8::from(*color)),
+            Some(Variant::Color3uint8(color)) => Some(*color),
+            _ => None,
+        };
+        let red = Some(Color3uint8::new(255, 0, 0));
+
+        for encoded in &[auto, float_only, uint8_only] {
+            let decoded = crate::from_str_default(encoded).unwrap();
+            let part = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+            let value = decoded.get_by_ref(part.children()[0]).unwrap();
+
+            assert_eq!(as_uint8(part.properties.get("Color")), red);
+            assert_eq!(as_uint8(value.properties.get("Value")), red);
+        }
+    }
+
+    #[test]
+    fn physical_properties_format() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(InstanceBuilder::new("Part").with_property(
+                    "CustomPhysicalProperties",
+                    PhysicalProperties::Custom(CustomPhysicalProperties {
+                        density: 0.5,
+                        friction: 1.0,
+                        elasticity: 1.5,
+                        friction_weight: 2.0,
+                        elasticity_weight: 2.5,
+                    }),
+                ))
+                .with_child(
+                    InstanceBuilder::new("Part")
+                        .with_property("CustomPhysicalProperties", PhysicalProperties::Default),
+                ),
+        );
+
+        let encode = |format| {
+            let options = EncodeOptions::new().physical_properties_format(format);
+            crate::to_string(&dom, &[dom.root_ref()], options).unwrap()
+        };
+
+        let modern = encode(PhysicalPropertiesFormat::Modern);
+        assert_eq!(
+            modern
+                .matches(r#"<PhysicalProperties name="CustomPhysicalProperties">"#)
+                .count(),
+            2
+        );
+        assert!(modern.contains("<CustomPhysics>true</CustomPhysics>"));
+        assert!(modern.contains("<Density>0.5</Density>"));
+        assert!(modern.contains("<CustomPhysics>false</CustomPhysics>"));
+        assert!(!modern.contains(r#"<float name="Friction">"#));
+
+        let legacy = encode(PhysicalPropertiesFormat::Legacy);
+        assert!(!legacy.contains("<PhysicalProperties"));
+        assert!(!legacy.contains("Density"));
+        assert_eq!(
+            legacy
+                .matches(r#"<float name="Friction">1</float>"#)
+                .count(),
+            1
+        );
+        assert_eq!(
+            legacy
+                .matches(r#"<float name="Elasticity">1.5</float>"#)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn property_filter() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(InstanceBuilder::new("Script").with_property("Source", "print('hi')"))
+                .with_child(
+                    InstanceBuilder::new("ModuleScript").with_property("Source", "return {}"),
+                )
+                .with_child(InstanceBuilder::new("StringValue").with_property("Value", "kept")),
+        );
+
+        let options = EncodeOptions::new()
+            .property_filter(|_class_name, property_name, _value| property_name != "Source");
+        let encoded = crate::to_string(&dom, &[dom.root_ref()], options).unwrap();
+
+        assert!(!encoded.contains("Source"));
+        assert!(!encoded.contains("print('hi')"));
+        assert!(encoded.contains("kept"));
+        assert!(encoded.contains("ModuleScript"));
+    }
+
+    #[test]
+    fn respect_archivable() {
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_name("Saved")
+                .with_child(
+                    InstanceBuilder::new("Folder")
+                        .with_name("Temporary")
+                        .with_property("Archivable", false)
+                        .with_child(InstanceBuilder::new("Part").with_name("TemporaryPart")),
+                )
+                .with_child(
+                    InstanceBuilder::new("Folder")
+                        .with_name("Kept")
+                        .with_property("Archivable", true),
+                ),
+        );
+
+        let options = EncodeOptions::new().respect_archivable(true);
+        let encoded = crate::to_string(&dom, &[dom.root_ref()], options).unwrap();
+
+        assert!(encoded.contains("Saved"));
+        assert!(encoded.contains("Kept"));
+        assert!(!encoded.contains("Temporary"));
+
+        let encoded = crate::to_string_default(&dom, &[dom.root_ref()]).unwrap();
+        assert!(encoded.contains("TemporaryPart"));
+    }
+}
@@ -1,6 +1,9 @@
 use std::{fmt::Write as FmtWrite, io::Write};
 
-use xml::writer::{EmitterConfig, EventWriter};
+use xml::{
+    reader::{ParserConfig, XmlEvent as XmlReadEvent},
+    writer::{EmitterConfig, EventWriter},
+};
 
 pub use xml::writer::XmlEvent as XmlWriteEvent;
 
@@ -93,6 +96,68 @@ impl<W: Write> XmlEventWriter<W> {
         self.write(XmlWriteEvent::end_element())
     }
 
+    /// Writes a property that was read with
+    /// `XmlEventReader::read_raw_tag_contents`, wrapping its contents in a tag
+    /// named after its type.
+    pub fn write_raw_property(
+        &mut self,
+        type_name: &str,
+        property_name: &str,
+        contents: &str,
+    ) -> Result<(), NewEncodeError> {
+        let wrapped = format!("<raw>{}</raw>", contents);
+        let mut reader = ParserConfig::new().create_reader(wrapped.as_bytes());
+        let mut events = Vec::new();
+
+        loop {
+            match reader.next() {
+                Ok(XmlReadEvent::EndDocument) => break,
+                Ok(event) => events.push(event),
+                Err(source) => {
+                    return Err(self.error(EncodeErrorKind::InvalidRawProperty {
+                        property_name: property_name.to_owned(),
+                        source,
+                    }))
+                }
+            }
+        }
+
+        self.write(XmlWriteEvent::start_element(type_name).attr("name", property_name))?;
+
+        // The first and last elements are the wrapper that we added.
+        let mut depth = 0;
+
+        for event in &events {
+            match event {
+                XmlReadEvent::StartElement {
+                    name, attributes, ..
+                } => {
+                    depth += 1;
+
+                    if depth > 1 {
+                        let mut element = XmlWriteEvent::start_element(name.borrow());
+                        for attribute in attributes {
+                            element = element.attr(attribute.name.borrow(), &attribute.value);
+                        }
+                        self.write(element)?;
+                    }
+                }
+                XmlReadEvent::EndElement { .. } => {
+                    if depth > 1 {
+                        self.write(XmlWriteEvent::end_element())?;
+                    }
+
+                    depth -= 1;
+                }
+                XmlReadEvent::Characters(text) => self.write(XmlWriteEvent::characters(text))?,
+                XmlReadEvent::CData(text) => self.write(XmlWriteEvent::cdata(text))?,
+                _ => {}
+            }
+        }
+
+        self.write(XmlWriteEvent::end_element())
+    }
+
     /// Writes a list of values that implement `Display`, with each wrapped in
     /// an associated tag. This method uses the same optimization as
     /// `write_characters` to avoid extra allocations.
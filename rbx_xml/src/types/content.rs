@@ -1,17 +1,23 @@
 use std::io::{Read, Write};
 
-use rbx_dom_weak::types::Content;
+use rbx_dom_weak::types::{Content, Ref};
 
 use crate::{
     core::XmlType,
+    deserializer::ParseState,
     deserializer_core::{XmlEventReader, XmlReadEvent},
     error::{DecodeError, DecodeErrorKind, EncodeError},
+    serializer::EmitState,
     serializer_core::{XmlEventWriter, XmlWriteEvent},
 };
 
 // A Content type is serialized as either:
 // <null></null>, which indicates an empty content value
 // <url>something</url>, where 'something' is a URL to use for content.
+// <object>referent</object>, where 'referent' is the referent of an instance.
+//
+// Like Ref properties, the object form needs to know about other instances, so
+// it's handled by `read_content` and `write_content` instead of `XmlType`.
 impl XmlType for Content {
     const XML_TAG_NAME: &'static str = "Content";
 
@@ -66,12 +72,110 @@ impl XmlType for Content {
     }
 }
 
+pub fn write_content<W: Write>(
+    writer: &mut XmlEventWriter<W>,
+    xml_property_name: &str,
+    value: &Content,
+    state: &mut EmitState,
+) -> Result<(), EncodeError> {
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => return value.write_outer_xml(xml_property_name, writer),
+    };
+
+    writer.write(
+        XmlWriteEvent::start_element(Content::XML_TAG_NAME).attr("name", xml_property_name),
+    )?;
+    writer.write(XmlWriteEvent::start_element("object"))?;
+    writer.write_characters(state.map_id(object))?;
+    writer.write(XmlWriteEvent::end_element())?;
+    writer.write(XmlWriteEvent::end_element())?;
+
+    Ok(())
+}
+
+pub fn read_content<R: Read>(
+    reader: &mut XmlEventReader<R>,
+    id: Ref,
+    property_name: &str,
+    state: &mut ParseState,
+) -> Result<Content, DecodeError> {
+    reader.expect_start_with_name(Content::XML_TAG_NAME)?;
+
+    let (is_object, is_empty) = match reader.expect_peek()? {
+        XmlReadEvent::StartElement { name, .. } => (name.local_name == "object", false),
+        XmlReadEvent::EndElement { .. } => (false, true),
+        _ => (false, false),
+    };
+
+    let value = if is_object {
+        let referent = reader.read_tag_contents("object")?;
+
+        // Just like Ref properties, the instance this points to might not
+        // have been read yet, so the value is filled in after the whole file
+        // has been read.
+        state.add_content_rewrite(id, property_name.to_owned(), referent);
+
+        Content::new()
+    } else if is_empty {
+        // Some tools write empty content as an empty tag instead of <null>.
+        Content::new()
+    } else {
+        Content::read_xml(reader)?
+    };
+
+    reader.expect_end_with_name(Content::XML_TAG_NAME)?;
+
+    Ok(value)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    use rbx_dom_weak::{types::Variant, InstanceBuilder, WeakDom};
+
     use crate::test_util;
 
+    #[test]
+    fn object_round_trip() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="ImageLabel" referent="label">
+                    <Properties>
+                        <Content name="ImageContent">
+                            <object>image</object>
+                        </Content>
+                    </Properties>
+                    <Item class="EditableImage" referent="image">
+                        <Properties />
+                    </Item>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = crate::DecodeOptions::new()
+            .property_behavior(crate::DecodePropertyBehavior::ReadUnknown);
+        let decoded = crate::from_str(document, options).unwrap();
+        let label = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+        let image_ref = label.children()[0];
+
+        assert_eq!(
+            label.properties.get("ImageContent"),
+            Some(&Variant::Content(Content::from_object(image_ref)))
+        );
+
+        let mut dom = WeakDom::new(InstanceBuilder::new("ImageLabel"));
+        let image_ref = dom.insert(dom.root_ref(), InstanceBuilder::new("EditableImage"));
+        dom.root_mut()
+            .set_property("ImageContent", Content::from_object(image_ref));
+
+        let options = crate::EncodeOptions::new()
+            .property_behavior(crate::EncodePropertyBehavior::WriteUnknown);
+        let encoded = crate::to_string(&dom, &[dom.root_ref()], options).unwrap();
+        assert!(encoded.contains("<object>1</object>"));
+    }
+
     #[test]
     fn round_trip_content_url() {
         test_util::test_xml_round_trip(&Content::from("url://not/really/a/url"));
@@ -33,11 +33,12 @@ use std::io::{Read, Write};
 
 use rbx_dom_weak::types::{
     Axes, BinaryString, CFrame, Color3, Color3uint8, ColorSequence, Content, Enum, Faces,
-    NumberRange, NumberSequence, PhysicalProperties, Ray, Rect, Ref, UDim, UDim2, Variant, Vector2,
-    Vector2int16, Vector3, Vector3int16,
+    NumberRange, NumberSequence, PhysicalProperties, Ray, Rect, Ref, UDim, UDim2, Variant,
+    VariantType, Vector2, Vector2int16, Vector3, Vector3int16,
 };
 
 use crate::{
+    conversion::has_conversion,
     core::XmlType,
     deserializer::ParseState,
     deserializer_core::XmlEventReader,
@@ -47,6 +48,7 @@ use crate::{
 };
 
 use self::{
+    content::{read_content, write_content},
     referent::{read_ref, write_ref},
     shared_string::{read_shared_string, write_shared_string},
 };
@@ -76,17 +78,63 @@ macro_rules! declare_rbx_types {
                 },
 
                 self::referent::XML_TAG_NAME => Ok(Some(Variant::Ref(read_ref(reader, instance_id, property_name, state)?))),
+                <Content>::XML_TAG_NAME => Ok(Some(Variant::Content(read_content(reader, instance_id, property_name, state)?))),
                 self::shared_string::XML_TAG_NAME => read_shared_string(reader, instance_id, property_name, state).map(Some),
 
                 _ => {
-                    state.unknown_type_visited(instance_id, property_name, xml_type_name);
-                    reader.eat_unknown_tag()?;
+                    if state.preserve_unknown_types() {
+                        let contents = reader.read_raw_tag_contents()?;
+                        state.add_unknown_property(instance_id, property_name, xml_type_name, contents);
+                    } else {
+                        state.unknown_type_visited(instance_id, property_name, xml_type_name);
+                        reader.eat_unknown_tag()?;
+                    }
 
                     Ok(None)
                 },
             }
         }
 
+        /// Reads a Roblox property value from the XML event stream as the given
+        /// type, no matter what type the name of its tag says it is.
+        ///
+        /// Returns `Ok(None)` without reading anything if values of the given
+        /// type can't be read this way, like `Ref` values, or if the tag names
+        /// a type that can already be converted to the given type, like a
+        /// `Color3` tag for a `Color3uint8` property.
+        pub fn read_value_xml_as<R: Read>(
+            reader: &mut XmlEventReader<R>,
+            xml_type_name: &str,
+            ty: VariantType,
+        ) -> Result<Option<Variant>, DecodeError> {
+            if let Some(tag_type) = xml_tag_type(xml_type_name) {
+                if has_conversion(tag_type, ty) {
+                    return Ok(None);
+                }
+            }
+
+            match ty {
+                $(VariantType::$variant_name => {
+                    reader.expect_start_with_name(xml_type_name)?;
+                    let value = <$inner_type>::read_xml(reader)?;
+                    reader.expect_end_with_name(xml_type_name)?;
+
+                    Ok(Some(Variant::$variant_name(value)))
+                },)*
+
+                _ => Ok(None),
+            }
+        }
+
+        /// Returns the type of value held by tags with the given name, if it's
+        /// one of the simple types that `read_value_xml_as` can read.
+        pub fn xml_tag_type(xml_type_name: &str) -> Option<VariantType> {
+            match xml_type_name {
+                $(<$inner_type>::XML_TAG_NAME => Some(VariantType::$variant_name),)*
+                _ => None,
+            }
+        }
+
         /// Writes a Roblox property value with the given XML name to the XML
         /// stream.
         pub fn write_value_xml<W: Write>(
@@ -104,10 +152,14 @@ macro_rules! declare_rbx_types {
                     (*value as i32).write_outer_xml(xml_property_name, writer),
 
                 Variant::Ref(value) => write_ref(writer, xml_property_name, *value, state),
+                Variant::Content(value) => write_content(writer, xml_property_name, value, state),
                 Variant::SharedString(value) => write_shared_string(writer, xml_property_name, value, state),
 
                 unknown => {
-                    Err(writer.error(EncodeErrorKind::UnsupportedPropertyType(unknown.ty())))
+                    Err(writer.error(EncodeErrorKind::UnsupportedPropertyType {
+                        property_name: xml_property_name.to_owned(),
+                        ty: unknown.ty(),
+                    }))
                 },
             }
         }
@@ -122,7 +174,6 @@ declare_rbx_types! {
     Color3: Color3,
     Color3uint8: Color3uint8,
     ColorSequence: ColorSequence,
-    Content: Content,
     Enum: Enum,
     Faces: Faces,
     Float32: f32,
@@ -1,9 +1,12 @@
-use std::io::{Read, Write};
+use std::{
+    convert::TryFrom,
+    io::{Read, Write},
+};
 
 use crate::{
     core::XmlType,
     deserializer_core::XmlEventReader,
-    error::{DecodeError, EncodeError},
+    error::{DecodeError, DecodeErrorKind, EncodeError},
     serializer_core::XmlEventWriter,
 };
 
@@ -70,10 +73,35 @@ int_type!(i64, "int64");
 
 // Convenience implementations for other types.
 // FIXME: This feels weird to bundle into the XmlType trait.
-int_type!(i16, "<NOT A REAL TYPE>");
+impl XmlType for i16 {
+    const XML_TAG_NAME: &'static str = "<NOT A REAL TYPE>";
+
+    fn write_xml<W: Write>(&self, writer: &mut XmlEventWriter<W>) -> Result<(), EncodeError> {
+        writer.write_characters(*self)
+    }
+
+    fn read_xml<R: Read>(reader: &mut XmlEventReader<R>) -> Result<Self, DecodeError> {
+        // Hand-edited files can contain values that don't fit into 16 bits.
+        // We read a wider integer first so that we can report those values
+        // instead of a generic parse error.
+        let value: i32 = reader
+            .read_characters()?
+            .parse()
+            .map_err(|e| reader.error(e))?;
+
+        i16::try_from(value).map_err(|_| {
+            reader.error(DecodeErrorKind::IntegerOutOfRange {
+                value: value.into(),
+                type_name: "i16",
+            })
+        })
+    }
+}
 
 #[cfg(test)]
 mod test {
+    use rbx_dom_weak::types::Variant;
+
     use crate::{core::XmlType, deserializer_core::XmlEventReader, test_util};
 
     #[test]
@@ -96,6 +124,41 @@ mod test {
         test_util::test_xml_round_trip(&281474976710656i64);
     }
 
+    #[test]
+    fn float_and_double_tags() {
+        let document = r#"
+            <roblox version="4">
+                <Item class="Folder" referent="0">
+                    <Properties>
+                        <float name="Single">0.5</float>
+                        <double name="Double">0.25</double>
+                    </Properties>
+                </Item>
+            </roblox>
+        "#;
+
+        let options = crate::DecodeOptions::new()
+            .property_behavior(crate::DecodePropertyBehavior::ReadUnknown);
+        let dom = crate::from_str(document, options).unwrap();
+        let folder = dom.get_by_ref(dom.root().children()[0]).unwrap();
+
+        assert_eq!(
+            folder.properties.get("Single"),
+            Some(&Variant::Float32(0.5))
+        );
+        assert_eq!(
+            folder.properties.get("Double"),
+            Some(&Variant::Float64(0.25))
+        );
+
+        let options = crate::EncodeOptions::new()
+            .property_behavior(crate::EncodePropertyBehavior::WriteUnknown);
+        let encoded = crate::to_string(&dom, &[folder.referent()], options).unwrap();
+
+        assert!(encoded.contains(r#"<float name="Single">0.5</float>"#));
+        assert!(encoded.contains(r#"<double name="Double">0.25</double>"#));
+    }
+
     #[test]
     fn test_inf_and_nan_deserialize() {
         test_util::test_xml_deserialize(r#"<float name="foo">INF</float>"#, &std::f32::INFINITY);
@@ -68,4 +68,42 @@ mod test {
 
         test_util::test_xml_round_trip(&test_input);
     }
+
+    #[test]
+    fn de_none() {
+        test_util::test_xml_deserialize(
+            r#"<OptionalCoordinateFrame name="foo"></OptionalCoordinateFrame>"#,
+            &None,
+        );
+
+        test_util::test_xml_deserialize(r#"<OptionalCoordinateFrame name="foo"/>"#, &None);
+    }
+
+    #[test]
+    fn de_some() {
+        test_util::test_xml_deserialize(
+            r#"
+                <OptionalCoordinateFrame name="foo">
+                    <CFrame>
+                        <X>1</X>
+                        <Y>2</Y>
+                        <Z>3</Z>
+                        <R00>1</R00>
+                        <R01>0</R01>
+                        <R02>0</R02>
+                        <R10>0</R10>
+                        <R11>1</R11>
+                        <R12>0</R12>
+                        <R20>0</R20>
+                        <R21>0</R21>
+                        <R22>1</R22>
+                    </CFrame>
+                </OptionalCoordinateFrame>
+            "#,
+            &Some(CFrame::new(
+                Vector3::new(1.0, 2.0, 3.0),
+                Matrix3::identity(),
+            )),
+        );
+    }
 }
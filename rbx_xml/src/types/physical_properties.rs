@@ -144,4 +144,30 @@ mod test {
             }),
         );
     }
+
+    #[test]
+    fn round_trip_part_default_physics() {
+        use rbx_dom_weak::{types::Variant, InstanceBuilder, WeakDom};
+
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Part")
+                .with_property("CustomPhysicalProperties", PhysicalProperties::Default),
+        );
+
+        let mut encoded = Vec::new();
+        crate::to_writer_default(&mut encoded, &dom, &[dom.root_ref()]).unwrap();
+
+        // A part using its material's physics shouldn't gain a custom block.
+        let encoded = String::from_utf8(encoded).unwrap();
+        assert!(encoded.contains("<CustomPhysics>false</CustomPhysics>"));
+        assert!(!encoded.contains("<Density>"));
+
+        let decoded = crate::from_str_default(&encoded).unwrap();
+        let part = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+
+        assert_eq!(
+            part.properties.get("CustomPhysicalProperties"),
+            Some(&Variant::PhysicalProperties(PhysicalProperties::Default))
+        );
+    }
 }
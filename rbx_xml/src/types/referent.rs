@@ -61,3 +61,30 @@ pub fn read_ref<R: Read>(
 
     Ok(Ref::none())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::{types::Variant, InstanceBuilder, WeakDom};
+
+    #[test]
+    fn null_ref_round_trip() {
+        let dom =
+            WeakDom::new(InstanceBuilder::new("ObjectValue").with_property("Value", Ref::none()));
+
+        let mut encoded = Vec::new();
+        crate::to_writer_default(&mut encoded, &dom, &[dom.root_ref()]).unwrap();
+
+        let encoded = String::from_utf8(encoded).unwrap();
+        assert!(encoded.contains(r#"<Ref name="Value">null</Ref>"#));
+
+        let decoded = crate::from_str_default(&encoded).unwrap();
+        let object_value = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+
+        assert_eq!(
+            object_value.properties.get("Value"),
+            Some(&Variant::Ref(Ref::none()))
+        );
+    }
+}
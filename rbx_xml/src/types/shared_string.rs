@@ -48,3 +48,46 @@ pub fn read_shared_string<R: Read>(
     // later.
     Ok(Variant::BinaryString(BinaryString::new()))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rbx_dom_weak::{InstanceBuilder, WeakDom};
+
+    use crate::{DecodeOptions, DecodePropertyBehavior, EncodeOptions, EncodePropertyBehavior};
+
+    #[test]
+    fn shared_strings_are_deduplicated() {
+        let blob = SharedString::new(b"\x00\x01\x02 shared data \xff".to_vec());
+        let other_blob = SharedString::new(b"different data".to_vec());
+
+        let dom = WeakDom::new(
+            InstanceBuilder::new("Folder")
+                .with_child(InstanceBuilder::new("Folder").with_property("Data", blob.clone()))
+                .with_child(InstanceBuilder::new("Folder").with_property("Data", blob.clone()))
+                .with_child(InstanceBuilder::new("Folder").with_property("Data", other_blob)),
+        );
+
+        let encode_options =
+            EncodeOptions::new().property_behavior(EncodePropertyBehavior::NoReflection);
+        let encoded = crate::to_string(&dom, &[dom.root_ref()], encode_options).unwrap();
+
+        assert_eq!(encoded.matches("<SharedString md5=").count(), 2);
+        assert_eq!(encoded.matches(r#"<SharedString name="Data">"#).count(), 3);
+
+        let decode_options =
+            DecodeOptions::new().property_behavior(DecodePropertyBehavior::NoReflection);
+        let decoded = crate::from_str(&encoded, decode_options).unwrap();
+        let folder = decoded.get_by_ref(decoded.root().children()[0]).unwrap();
+
+        for &child_ref in &folder.children()[..2] {
+            let child = decoded.get_by_ref(child_ref).unwrap();
+
+            match child.properties.get("Data") {
+                Some(Variant::SharedString(value)) => assert_eq!(value.data(), blob.data()),
+                other => panic!("expected a SharedString, got {:?}", other),
+            }
+        }
+    }
+}
@@ -5,7 +5,7 @@ use rbx_dom_weak::types::{UDim, UDim2};
 use crate::{
     core::XmlType,
     deserializer_core::XmlEventReader,
-    error::{DecodeError, EncodeError},
+    error::{DecodeError, DecodeErrorKind, EncodeError},
     serializer_core::XmlEventWriter,
 };
 
@@ -20,7 +20,7 @@ impl XmlType for UDim {
     }
 
     fn read_xml<R: Read>(reader: &mut XmlEventReader<R>) -> Result<Self, DecodeError> {
-        let scale: f32 = reader.read_value_in_tag("S")?;
+        let scale = read_scale(reader, "S")?;
         let offset: i32 = reader.read_value_in_tag("O")?;
 
         Ok(UDim { scale, offset })
@@ -40,9 +40,9 @@ impl XmlType for UDim2 {
     }
 
     fn read_xml<R: Read>(reader: &mut XmlEventReader<R>) -> Result<Self, DecodeError> {
-        let x_scale: f32 = reader.read_value_in_tag("XS")?;
+        let x_scale = read_scale(reader, "XS")?;
         let x_offset: i32 = reader.read_value_in_tag("XO")?;
-        let y_scale: f32 = reader.read_value_in_tag("YS")?;
+        let y_scale = read_scale(reader, "YS")?;
         let y_offset: i32 = reader.read_value_in_tag("YO")?;
 
         Ok(UDim2 {
@@ -52,6 +52,19 @@ impl XmlType for UDim2 {
     }
 }
 
+/// Reads the scale component of a UDim, which must be a finite number.
+fn read_scale<R: Read>(reader: &mut XmlEventReader<R>, tag_name: &str) -> Result<f32, DecodeError> {
+    let scale: f32 = reader.read_value_in_tag(tag_name)?;
+
+    if scale.is_finite() {
+        Ok(scale)
+    } else {
+        Err(reader.error(DecodeErrorKind::InvalidContent(
+            "UDim scale must be a finite number",
+        )))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -95,4 +108,37 @@ mod test {
             &UDim2::new(UDim::new(0.5, 1), UDim::new(1.5, 2)),
         );
     }
+
+    #[test]
+    fn de_udim_float_offset() {
+        let source = r#"
+            <UDim name="foo">
+                <S>0.5</S>
+                <O>1.5</O>
+            </UDim>
+        "#;
+
+        let mut reader = XmlEventReader::from_source(source.as_bytes());
+        reader.next().unwrap().unwrap(); // Eat StartDocument event
+
+        assert!(UDim::read_outer_xml(&mut reader).is_err());
+    }
+
+    #[test]
+    fn de_udim2_nan_scale() {
+        let source = r#"
+            <UDim2 name="foo">
+                <XS>nan</XS>
+                <XO>0</XO>
+                <YS>0</YS>
+                <YO>0</YO>
+            </UDim2>
+        "#;
+
+        let mut reader = XmlEventReader::from_source(source.as_bytes());
+        reader.next().unwrap().unwrap(); // Eat StartDocument event
+        let error = UDim2::read_outer_xml(&mut reader).unwrap_err();
+
+        assert!(error.to_string().contains("finite"));
+    }
 }
@@ -66,4 +66,50 @@ mod test {
     fn round_trip_vector3int16() {
         test_util::test_xml_round_trip(&Vector3int16::new(1234, 4567, 8913));
     }
+
+    #[test]
+    fn de_vector3int16_boundary() {
+        test_util::test_xml_deserialize(
+            r#"
+                <Vector3int16 name="foo">
+                    <X>32767</X>
+                    <Y>-32768</Y>
+                    <Z>0</Z>
+                </Vector3int16>
+            "#,
+            &Vector3int16::new(32767, -32768, 0),
+        );
+    }
+
+    #[test]
+    fn de_vector3int16_overflow() {
+        let source = r#"
+            <Vector3int16 name="foo">
+                <X>40000</X>
+                <Y>0</Y>
+                <Z>0</Z>
+            </Vector3int16>
+        "#;
+
+        let mut reader = XmlEventReader::from_source(source.as_bytes());
+        reader.next().unwrap().unwrap(); // Eat StartDocument event
+        let error = Vector3int16::read_outer_xml(&mut reader).unwrap_err();
+
+        assert!(error.to_string().contains("40000"));
+    }
+
+    #[test]
+    fn de_vector2int16_overflow() {
+        let source = r#"
+            <Vector2int16 name="foo">
+                <X>0</X>
+                <Y>-32769</Y>
+            </Vector2int16>
+        "#;
+
+        let mut reader = XmlEventReader::from_source(source.as_bytes());
+        reader.next().unwrap().unwrap(); // Eat StartDocument event
+
+        assert!(Vector2int16::read_outer_xml(&mut reader).is_err());
+    }
 }